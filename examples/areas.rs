@@ -0,0 +1,72 @@
+use iced::widget::{button, text};
+use iced::{Element, Sandbox, Settings};
+use iced_taffy::grid;
+use taffy::prelude::*;
+
+mod common {
+    pub mod colors;
+    pub mod layout_timer;
+    pub mod rect;
+}
+use common::colors::*;
+use common::layout_timer::LayoutTimer;
+use common::rect::rect;
+
+pub fn main() -> iced::Result {
+    Example::run(Settings::default())
+}
+
+struct Example {
+    click_count: u32,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Message {
+    Increment,
+}
+
+impl Sandbox for Example {
+    type Message = Message;
+
+    fn new() -> Self {
+        Example { click_count: 0 }
+    }
+
+    fn title(&self) -> String {
+        String::from("Grid template areas - Iced")
+    }
+
+    fn update(&mut self, message: Message) {
+        match message {
+            Message::Increment => {
+                self.click_count += 1;
+            }
+        }
+    }
+
+    fn view(&self) -> Element<Message> {
+        let content = grid()
+            .with_areas(&["header header", "nav main", "foot foot"])
+            .with_columns(vec![points(200.), flex(1.)])
+            .with_rows(vec![points(60.), flex(1.), points(40.)])
+            .style(|style| {
+                style.size.width = percent(1.);
+                style.size.height = percent(1.);
+                style.gap = points(10.);
+            })
+            .with_child_in_area(rect(20.0, COLOR1), "header")
+            .with_child_in_area(rect(20.0, COLOR2), "nav")
+            .with_styled_child_in_area(
+                button(text(format!("Clicked {} times", self.click_count)))
+                    .on_press(Message::Increment),
+                "main",
+                |style| {
+                    style.align_self = Some(AlignSelf::Center);
+                    style.justify_self = Some(AlignSelf::Center);
+                },
+            )
+            .with_child_in_area(rect(20.0, COLOR3), "foot");
+
+        LayoutTimer::new(content).into()
+    }
+}
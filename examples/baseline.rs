@@ -0,0 +1,67 @@
+use iced::widget::{text, text_input};
+use iced::{Element, Sandbox, Settings};
+use iced_taffy::{grid, text_baseline, with_baseline};
+use taffy::prelude::*;
+
+mod common {
+    pub mod layout_timer;
+}
+use common::layout_timer::LayoutTimer;
+
+pub fn main() -> iced::Result {
+    Example::run(Settings::default())
+}
+
+struct Example {
+    name: String,
+}
+
+#[derive(Debug, Clone)]
+enum Message {
+    NameChanged(String),
+}
+
+impl Sandbox for Example {
+    type Message = Message;
+
+    fn new() -> Self {
+        Example {
+            name: String::new(),
+        }
+    }
+
+    fn title(&self) -> String {
+        String::from("Baseline alignment - Iced")
+    }
+
+    fn update(&mut self, message: Message) {
+        match message {
+            Message::NameChanged(name) => {
+                self.name = name;
+            }
+        }
+    }
+
+    fn view(&self) -> Element<Message> {
+        // Labels and inputs with different font sizes share a single text baseline. iced widgets
+        // do not report their baselines, so each one is given an estimate: the baseline of text
+        // of its font size, below the input's top padding.
+        let content = grid()
+            .with_columns(vec![auto(), auto(), flex(1.)])
+            .style(|style| {
+                style.align_items = Some(AlignItems::Baseline);
+                style.gap = points(10.);
+                style.padding = points(20.);
+            })
+            .with_child(with_baseline(text("Name").size(32), text_baseline(32.)))
+            .with_child(with_baseline(text("(required)").size(14), text_baseline(14.)))
+            .with_child(with_baseline(
+                text_input("Your name", &self.name, Message::NameChanged)
+                    .size(20)
+                    .padding(10),
+                10. + text_baseline(20.),
+            ));
+
+        LayoutTimer::new(content).into()
+    }
+}
@@ -0,0 +1,84 @@
+use iced::widget::{button, text};
+use iced::{Element, Sandbox, Settings};
+use iced_taffy::block;
+use taffy::prelude::*;
+
+mod common {
+    pub mod colors;
+    pub mod layout_timer;
+    pub mod rect;
+}
+use common::colors::*;
+use common::layout_timer::LayoutTimer;
+use common::rect::rect;
+
+pub fn main() -> iced::Result {
+    Example::run(Settings::default())
+}
+
+struct Example {
+    click_count: u32,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Message {
+    Increment,
+}
+
+impl Sandbox for Example {
+    type Message = Message;
+
+    fn new() -> Self {
+        Example { click_count: 0 }
+    }
+
+    fn title(&self) -> String {
+        String::from("Block layout - Iced")
+    }
+
+    fn update(&mut self, message: Message) {
+        match message {
+            Message::Increment => {
+                self.click_count += 1;
+            }
+        }
+    }
+
+    fn view(&self) -> Element<Message> {
+        const PARAGRAPH : &str = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua. Ut enim ad minim veniam, quis nostrud exercitation ullamco laboris nisi ut aliquip ex ea commodo consequat.";
+        let content = block()
+            .style(|style| {
+                style.padding = points(20.);
+            })
+            .with_styled_child(text("Settings").size(32), |style| {
+                style.margin.bottom = points(20.);
+            })
+            .with_styled_child(text(PARAGRAPH), |style| {
+                // Collapses with the margin above, leaving a 20px gap
+                style.margin.top = points(10.);
+                style.margin.bottom = points(10.);
+            })
+            .with_styled_child(rect(20.0, COLOR1), |style| {
+                style.size.height = points(40.);
+            })
+            .with_styled_child(rect(20.0, COLOR2), |style| {
+                style.size.width = percent(0.5);
+                style.size.height = points(40.);
+                style.margin.left = auto();
+                style.margin.right = auto();
+                style.margin.top = points(10.);
+            })
+            .with_styled_child(
+                text(format!("Button clicked {} times", self.click_count)),
+                |style| {
+                    style.margin.top = points(10.);
+                },
+            )
+            .with_styled_child(button("Increment").on_press(Message::Increment), |style| {
+                style.size.width = points(120.);
+                style.margin.left = auto();
+            });
+
+        LayoutTimer::new(content).into()
+    }
+}
@@ -0,0 +1,85 @@
+use iced::widget::{button, text};
+use iced::{Element, Sandbox, Settings};
+use iced_taffy::flex;
+use taffy::prelude::*;
+
+mod common {
+    pub mod colors;
+    pub mod layout_timer;
+    pub mod rect;
+}
+use common::colors::*;
+use common::layout_timer::LayoutTimer;
+use common::rect::rect;
+
+pub fn main() -> iced::Result {
+    Example::run(Settings::default())
+}
+
+struct Example {
+    click_count: u32,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Message {
+    Increment,
+}
+
+impl Sandbox for Example {
+    type Message = Message;
+
+    fn new() -> Self {
+        Example { click_count: 0 }
+    }
+
+    fn title(&self) -> String {
+        String::from("Flexbox - Iced")
+    }
+
+    fn update(&mut self, message: Message) {
+        match message {
+            Message::Increment => {
+                self.click_count += 1;
+            }
+        }
+    }
+
+    fn view(&self) -> Element<Message> {
+        let toolbar = flex()
+            .direction(FlexDirection::Row)
+            .align_items(AlignItems::Center)
+            .with_column_gap(points(10.))
+            .with_child(button("Increment").on_press(Message::Increment))
+            .with_child(text(format!("Button clicked {} times", self.click_count)))
+            .with_styled_child(rect(20.0, BLACK), |style| {
+                style.margin.left = auto();
+            });
+
+        let mut chips = flex()
+            .direction(FlexDirection::Row)
+            .wrap(FlexWrap::Wrap)
+            .justify_content(JustifyContent::Center)
+            .with_column_gap(points(8.))
+            .with_row_gap(points(8.));
+        for color in [COLOR1, COLOR2, COLOR3, COLOR4, COLOR5, COLOR6, COLOR7, COLOR8] {
+            for size in [20.0, 40.0, 30.0] {
+                chips.add_child(rect(size, color));
+            }
+        }
+
+        let content = flex()
+            .direction(FlexDirection::Column)
+            .style(|style| {
+                style.size.width = percent(1.);
+                style.size.height = percent(1.);
+                style.padding = points(20.);
+                style.gap = points(20.);
+            })
+            .with_child(toolbar)
+            .with_styled_child(chips, |style| {
+                style.flex_grow = 1.;
+            });
+
+        LayoutTimer::new(content).into()
+    }
+}
@@ -0,0 +1,56 @@
+use iced::{Element, Sandbox, Settings};
+use iced_taffy::grid;
+use taffy::prelude::*;
+
+mod common {
+    pub mod colors;
+    pub mod layout_timer;
+    pub mod rect;
+}
+use common::colors::*;
+use common::layout_timer::LayoutTimer;
+use common::rect::rect;
+
+pub fn main() -> iced::Result {
+    Example::run(Settings::default())
+}
+
+struct Example;
+
+impl Sandbox for Example {
+    type Message = ();
+
+    fn new() -> Self {
+        Example
+    }
+
+    fn title(&self) -> String {
+        String::from("Masonry - Iced")
+    }
+
+    fn update(&mut self, _message: ()) {}
+
+    fn view(&self) -> Element<()> {
+        let colors = [COLOR1, COLOR2, COLOR3];
+        let mut content = grid()
+            .masonry()
+            .with_columns(vec![flex(1.), flex(1.), flex(1.), flex(1.)])
+            .style(|style| {
+                style.size.width = percent(1.);
+                style.gap = points(10.);
+            });
+
+        for index in 0..60 {
+            let height = 40. + ((index * 37) % 120) as f32;
+            content = content.with_styled_child(rect(20.0, colors[index % 3]), |style| {
+                style.size.height = points(height);
+                // Every seventh card spans two columns
+                if index % 7 == 0 {
+                    style.grid_column = span(2);
+                }
+            });
+        }
+
+        LayoutTimer::new(content).into()
+    }
+}
@@ -0,0 +1,73 @@
+use iced::{Element, Sandbox, Settings};
+use iced_taffy::{grid, GridTrackList};
+use taffy::prelude::*;
+
+mod common {
+    pub mod colors;
+    pub mod layout_timer;
+    pub mod rect;
+}
+use common::colors::*;
+use common::layout_timer::LayoutTimer;
+use common::rect::rect;
+
+pub fn main() -> iced::Result {
+    Example::run(Settings::default())
+}
+
+struct Example;
+
+impl Sandbox for Example {
+    type Message = ();
+
+    fn new() -> Self {
+        Example
+    }
+
+    fn title(&self) -> String {
+        String::from("Named grid lines - Iced")
+    }
+
+    fn update(&mut self, _message: ()) {}
+
+    fn view(&self) -> Element<()> {
+        let content = grid()
+            .with_named_columns(
+                GridTrackList::new()
+                    .line("sidebar-start")
+                    .track(points(200.))
+                    .line("sidebar-end")
+                    .line("content-start")
+                    .track(flex(1.))
+                    .track(flex(1.))
+                    .line("content-end"),
+            )
+            .with_named_rows(
+                GridTrackList::new()
+                    .line("top")
+                    .track(points(60.))
+                    .line("body")
+                    .track(flex(1.))
+                    .line("bottom"),
+            )
+            .style(|style| {
+                style.size.width = percent(1.);
+                style.size.height = percent(1.);
+                style.gap = points(10.);
+            })
+            .with_placed_child(rect(20.0, COLOR1), |lines, style| {
+                style.grid_column = lines.columns("sidebar-start", "content-end");
+                style.grid_row = lines.rows("top", "body");
+            })
+            .with_placed_child(rect(20.0, COLOR2), |lines, style| {
+                style.grid_column = lines.columns("sidebar-start", "sidebar-end");
+                style.grid_row = lines.rows("body", "bottom");
+            })
+            .with_placed_child(rect(20.0, COLOR3), |lines, style| {
+                style.grid_column = lines.columns("content-start", "content-end");
+                style.grid_row = lines.rows("body", "bottom");
+            });
+
+        LayoutTimer::new(content).into()
+    }
+}
@@ -1,6 +1,6 @@
 use iced::widget::{button, text};
 use iced::{Element, Length, Sandbox, Settings};
-use iced_taffy::grid;
+use iced_taffy::{grid, with_content_key, with_z_index};
 use taffy::prelude::*;
 
 mod common {
@@ -58,14 +58,21 @@ impl Sandbox for Example {
             .with_child({
                 grid()
                     .with_styled_child(
-                        text(format!("Button clicked {} times", self.click_count)).size(32),
+                        // The text is only measured again when the count changes
+                        with_content_key(
+                            text(format!("Button clicked {} times", self.click_count)).size(32),
+                            self.click_count,
+                        ),
                         |style| {
                             style.align_self = Some(AlignSelf::Center);
                             style.justify_self = Some(AlignSelf::Center);
                         },
                     )
                     .with_styled_child(
-                        text(REALLY_LONG_PARAGRAPH).width(Length::Fixed(100.)),
+                        with_content_key(
+                            text(REALLY_LONG_PARAGRAPH).width(Length::Fixed(100.)),
+                            (),
+                        ),
                         |style| {
                             style.margin = points(40.);
                         },
@@ -92,7 +99,7 @@ impl Sandbox for Example {
                     .with_child(rect(20.0, COLOR13))
                     .with_child(rect(20.0, COLOR14))
                     .with_child(rect(20.0, COLOR15))
-                    .with_styled_child(rect(20.0, COLOR16), |style| {
+                    .with_styled_child(with_z_index(rect(20.0, COLOR16), 1), |style| {
                         style.position = Position::Absolute;
                         style.grid_row = line(1);
                         style.grid_column = line(1);
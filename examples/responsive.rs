@@ -0,0 +1,79 @@
+use iced::widget::button;
+use iced::{Element, Sandbox, Settings};
+use iced_taffy::{taffy_container, Display};
+use taffy::prelude::*;
+
+mod common {
+    pub mod colors;
+    pub mod layout_timer;
+    pub mod rect;
+}
+use common::colors::*;
+use common::layout_timer::LayoutTimer;
+use common::rect::rect;
+
+pub fn main() -> iced::Result {
+    Example::run(Settings::default())
+}
+
+struct Example {
+    display: Display,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Message {
+    NextDisplay,
+}
+
+impl Sandbox for Example {
+    type Message = Message;
+
+    fn new() -> Self {
+        Example {
+            display: Display::Grid,
+        }
+    }
+
+    fn title(&self) -> String {
+        String::from("Switchable display - Iced")
+    }
+
+    fn update(&mut self, message: Message) {
+        match message {
+            Message::NextDisplay => {
+                self.display = match self.display {
+                    Display::Grid => Display::Flex,
+                    Display::Flex => Display::Block,
+                    Display::Block | Display::None => Display::Grid,
+                };
+            }
+        }
+    }
+
+    fn view(&self) -> Element<Message> {
+        let mut cards = taffy_container()
+            .display(self.display)
+            .with_columns(vec![flex(1.), flex(1.), flex(1.)])
+            .direction(FlexDirection::Row)
+            .wrap(FlexWrap::Wrap)
+            .style(|style| {
+                style.gap = points(10.);
+            });
+        for color in [COLOR1, COLOR2, COLOR3, COLOR4, COLOR5, COLOR6] {
+            cards.add_child(rect(40.0, color));
+        }
+
+        let content = taffy_container()
+            .display(Display::Flex)
+            .direction(FlexDirection::Column)
+            .style(|style| {
+                style.size.width = percent(1.);
+                style.padding = points(20.);
+                style.gap = points(20.);
+            })
+            .with_child(button(format!("{:?}", self.display)).on_press(Message::NextDisplay))
+            .with_child(cards);
+
+        LayoutTimer::new(content).into()
+    }
+}
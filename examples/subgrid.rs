@@ -0,0 +1,64 @@
+use iced::{Element, Sandbox, Settings};
+use iced_taffy::grid;
+use taffy::prelude::*;
+
+mod common {
+    pub mod colors;
+    pub mod layout_timer;
+    pub mod rect;
+}
+use common::colors::*;
+use common::layout_timer::LayoutTimer;
+use common::rect::rect;
+
+pub fn main() -> iced::Result {
+    Example::run(Settings::default())
+}
+
+struct Example;
+
+impl Sandbox for Example {
+    type Message = ();
+
+    fn new() -> Self {
+        Example
+    }
+
+    fn title(&self) -> String {
+        String::from("Subgrid - Iced")
+    }
+
+    fn update(&mut self, _message: ()) {}
+
+    fn view(&self) -> Element<()> {
+        let mut content = grid()
+            .with_columns(vec![auto(), flex(1.), points(120.)])
+            .with_rows(vec![points(40.), points(40.), points(40.), points(40.)])
+            .style(|style| {
+                style.size.width = percent(1.);
+                style.gap = points(10.);
+            });
+
+        // Each row is its own grid, but its cells line up with the columns of the parent grid
+        for row in 0..4 {
+            let label_width = 40. + 30. * row as f32;
+            let cells = grid()
+                .subgrid_columns()
+                .with_child(rect(label_width, COLOR1))
+                .with_child(rect(20.0, COLOR2))
+                .with_child(rect(20.0, COLOR3));
+            content = content.with_subgrid(cells, |style| {
+                style.grid_column = Line {
+                    start: line(1),
+                    end: line(4),
+                };
+                style.grid_row = Line {
+                    start: line(row + 1),
+                    end: span(1),
+                };
+            });
+        }
+
+        LayoutTimer::new(content).into()
+    }
+}
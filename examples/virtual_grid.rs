@@ -0,0 +1,46 @@
+use iced::widget::{scrollable, text};
+use iced::{Element, Sandbox, Settings};
+use iced_taffy::{virtual_grid, RowHeight};
+use taffy::prelude::*;
+
+mod common {
+    pub mod layout_timer;
+}
+use common::layout_timer::LayoutTimer;
+
+pub fn main() -> iced::Result {
+    Example::run(Settings::default())
+}
+
+const ROWS: usize = 100_000;
+const COLUMNS: usize = 4;
+
+struct Example;
+
+impl Sandbox for Example {
+    type Message = ();
+
+    fn new() -> Self {
+        Example
+    }
+
+    fn title(&self) -> String {
+        String::from("Virtual grid - Iced")
+    }
+
+    fn update(&mut self, _message: ()) {}
+
+    fn view(&self) -> Element<()> {
+        // Only the cells which are scrolled into view are ever built
+        let content = virtual_grid(ROWS * COLUMNS, |index| {
+            let (row, column) = (index / COLUMNS, index % COLUMNS);
+            text(format!("Row {} column {}", row + 1, column + 1)).into()
+        })
+        .with_columns(vec![points(80.), flex(1.), flex(1.), flex(2.)])
+        .row_height(RowHeight::Uniform(24.))
+        .with_column_gap(10.)
+        .with_row_gap(4.);
+
+        LayoutTimer::new(scrollable(content)).into()
+    }
+}
@@ -0,0 +1,215 @@
+//! Reporting the first baseline of a child to Taffy's baseline alignment
+//!
+//! iced's [`Widget`] trait has no notion of baselines, so children report them through a side
+//! channel: while a child's [`Widget::layout`] runs it may call [`report_first_baseline`], and
+//! the parent Taffy container picks the value up once `layout` returns. Nested iced_taffy
+//! containers report the baseline computed by their layout algorithm, and any other widget can
+//! be given a baseline by wrapping it with [`with_baseline`].
+//!
+//! iced's `text` does not report a baseline either, and its renderer does not expose the ascent
+//! of its font, so [`text_baseline`] estimates it from the font size.
+//!
+//! Only the direct children of a Taffy container are asked for a baseline, so each report is
+//! tagged with the address of the widget which made it. A baseline reported from inside another
+//! iced widget (such as a `column`, or a `container` with padding) is ignored, as that widget may
+//! have moved its content, and the widget is left without a baseline. iced_taffy's own wrapper
+//! widgets pass the baseline of their content on.
+
+use std::cell::Cell;
+
+use iced_native::event::{self, Event};
+use iced_native::renderer::Renderer;
+use iced_native::widget::{tree, Operation, Tree};
+use iced_native::{layout, mouse, overlay, renderer};
+use iced_native::{Clipboard, Element, Layout, Length, Point, Rectangle, Shell, Size, Widget};
+
+/// The height of a line of iced text above its baseline, as a fraction of the font size.
+///
+/// This is an approximation which holds for iced's default font.
+const TEXT_ASCENT: f32 = 0.8;
+
+thread_local! {
+    /// The latest reported first baseline, and the address of the widget which reported it
+    static REPORTED_FIRST_BASELINE: Cell<Option<(*const (), f32)>> = Cell::new(None);
+}
+
+/// The address of a widget, which identifies it while it is being laid out
+fn address<W: ?Sized>(widget: &W) -> *const () {
+    widget as *const W as *const ()
+}
+
+/// Report the first baseline of `widget`, which is being laid out, as an offset from its top edge
+pub(crate) fn report_first_baseline<W: ?Sized>(widget: &W, baseline: Option<f32>) {
+    REPORTED_FIRST_BASELINE
+        .with(|cell| cell.set(baseline.map(|baseline| (address(widget), baseline))));
+}
+
+/// Take the baseline reported by the most recent call to [`report_first_baseline`], if it was
+/// reported by `widget` itself
+pub(crate) fn take_first_baseline<W: ?Sized>(widget: &W) -> Option<f32> {
+    match REPORTED_FIRST_BASELINE.with(|cell| cell.take()) {
+        Some((reporter, baseline)) if reporter == address(widget) => Some(baseline),
+        _ => None,
+    }
+}
+
+/// Discard any reported baseline, before laying out a widget whose baseline will be taken
+pub(crate) fn clear_first_baseline() {
+    REPORTED_FIRST_BASELINE.with(|cell| cell.set(None));
+}
+
+/// The offset of the baseline of the first line of an iced `text` widget of the given font size.
+///
+/// This is an estimate for iced's default font, as the renderer does not expose font metrics.
+/// Other fonts may need a baseline of their own.
+pub fn text_baseline(size: f32) -> f32 {
+    (size * TEXT_ASCENT).round()
+}
+
+/// A widget which reports a fixed first baseline for its content
+pub struct WithBaseline<'a, Msg, R: Renderer> {
+    content: Element<'a, Msg, R>,
+    baseline: f32,
+}
+
+impl<'a, Msg, R: Renderer> WithBaseline<'a, Msg, R> {
+    /// Creates a [`WithBaseline`] whose first baseline is `baseline` pixels below its top edge
+    pub fn new(content: impl Into<Element<'a, Msg, R>>, baseline: f32) -> Self {
+        Self {
+            content: content.into(),
+            baseline,
+        }
+    }
+}
+
+/// Give `content` a first baseline `baseline` pixels below its top edge.
+///
+/// For iced `text`, use [`text_baseline`] with the text's font size.
+pub fn with_baseline<'a, Msg, R: Renderer>(
+    content: impl Into<Element<'a, Msg, R>>,
+    baseline: f32,
+) -> WithBaseline<'a, Msg, R> {
+    WithBaseline::new(content, baseline)
+}
+
+impl<'a, Msg, R: Renderer> Widget<Msg, R> for WithBaseline<'a, Msg, R> {
+    fn tag(&self) -> tree::Tag {
+        self.content.as_widget().tag()
+    }
+
+    fn state(&self) -> tree::State {
+        self.content.as_widget().state()
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        self.content.as_widget().children()
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        self.content.as_widget().diff(tree)
+    }
+
+    fn width(&self) -> Length {
+        self.content.as_widget().width()
+    }
+
+    fn height(&self) -> Length {
+        self.content.as_widget().height()
+    }
+
+    fn measure(&mut self, renderer: &R, limits: &layout::Limits) -> Size {
+        self.content.as_widget_mut().measure(renderer, limits)
+    }
+
+    fn layout(&mut self, renderer: &R, limits: &layout::Limits) -> layout::Node {
+        let node = self.content.as_widget_mut().layout(renderer, limits);
+        report_first_baseline(&*self, Some(self.baseline));
+        node
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &R,
+        operation: &mut dyn Operation<Msg>,
+    ) {
+        self.content
+            .as_widget()
+            .operate(tree, layout, renderer, operation)
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &R,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Msg>,
+    ) -> event::Status {
+        self.content.as_widget_mut().on_event(
+            tree,
+            event,
+            layout,
+            cursor_position,
+            renderer,
+            clipboard,
+            shell,
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+        renderer: &R,
+    ) -> mouse::Interaction {
+        self.content.as_widget().mouse_interaction(
+            tree,
+            layout,
+            cursor_position,
+            viewport,
+            renderer,
+        )
+    }
+
+    fn draw(
+        &mut self,
+        tree: &Tree,
+        renderer: &mut R,
+        theme: &R::Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+    ) {
+        self.content.as_widget_mut().draw(
+            tree,
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor_position,
+            viewport,
+        )
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'_>,
+        renderer: &R,
+    ) -> Option<overlay::Element<'b, Msg, R>> {
+        self.content.as_widget_mut().overlay(tree, layout, renderer)
+    }
+}
+
+impl<'a, Msg: 'a, R: Renderer + 'a> From<WithBaseline<'a, Msg, R>> for Element<'a, Msg, R> {
+    fn from(with_baseline: WithBaseline<'a, Msg, R>) -> Self {
+        Self::new(with_baseline)
+    }
+}
@@ -0,0 +1,753 @@
+//! A CSS Block layout widget based on Taffy
+//!
+//! Taffy does not (yet) provide a block layout algorithm, so [`BlockAlgorithm`] implements a
+//! subset of CSS block flow on top of Taffy's [`LayoutTree`](taffy::LayoutTree) abstraction:
+//!
+//!   - In-flow children are stacked vertically and stretch to fill the container's width
+//!     unless they have a definite width.
+//!   - `auto` horizontal margins absorb free space (so two `auto` margins center the child).
+//!   - Vertical margins of adjacent siblings collapse. The container always establishes a new
+//!     block formatting context, so children's margins never collapse through it.
+//!   - Absolutely positioned children are placed relative to the container's padding box.
+
+use iced_native::renderer::Renderer;
+
+use crate::container::{container_builders, container_widget, Algorithm, Container};
+use crate::tree::taffy;
+
+pub(crate) fn resolve_length_percentage(
+    value: taffy::LengthPercentage,
+    context: Option<f32>,
+) -> f32 {
+    match value {
+        taffy::LengthPercentage::Points(points) => points,
+        taffy::LengthPercentage::Percent(fraction) => context.map(|c| c * fraction).unwrap_or(0.0),
+    }
+}
+
+pub(crate) fn resolve_length_percentage_auto(
+    value: taffy::LengthPercentageAuto,
+    context: Option<f32>,
+) -> Option<f32> {
+    match value {
+        taffy::LengthPercentageAuto::Points(points) => Some(points),
+        taffy::LengthPercentageAuto::Percent(fraction) => {
+            Some(context.map(|c| c * fraction).unwrap_or(0.0))
+        }
+        taffy::LengthPercentageAuto::Auto => None,
+    }
+}
+
+fn resolve_dimension(value: taffy::Dimension, context: Option<f32>) -> Option<f32> {
+    match value {
+        taffy::Dimension::Points(points) => Some(points),
+        taffy::Dimension::Percent(fraction) => context.map(|c| c * fraction),
+        taffy::Dimension::Auto => None,
+    }
+}
+
+fn clamp(value: f32, min: Option<f32>, max: Option<f32>) -> f32 {
+    let value = max.map(|max| value.min(max)).unwrap_or(value);
+    min.map(|min| value.max(min)).unwrap_or(value)
+}
+
+/// Collapse two adjoining vertical margins as described in CSS2 §8.3.1
+fn collapse_margins(a: f32, b: f32) -> f32 {
+    if a >= 0.0 && b >= 0.0 {
+        a.max(b)
+    } else if a <= 0.0 && b <= 0.0 {
+        a.min(b)
+    } else {
+        a + b
+    }
+}
+
+/// The resolved size constraints of a node along both axes
+pub(crate) struct SizeConstraints {
+    size: taffy::Size<Option<f32>>,
+    min_size: taffy::Size<Option<f32>>,
+    max_size: taffy::Size<Option<f32>>,
+}
+
+impl SizeConstraints {
+    pub(crate) fn resolve(style: &taffy::Style, context: taffy::Size<Option<f32>>) -> Self {
+        Self {
+            size: taffy::Size {
+                width: resolve_dimension(style.size.width, context.width),
+                height: resolve_dimension(style.size.height, context.height),
+            },
+            min_size: taffy::Size {
+                width: resolve_dimension(style.min_size.width, context.width),
+                height: resolve_dimension(style.min_size.height, context.height),
+            },
+            max_size: taffy::Size {
+                width: resolve_dimension(style.max_size.width, context.width),
+                height: resolve_dimension(style.max_size.height, context.height),
+            },
+        }
+    }
+
+    pub(crate) fn clamp_width(&self, width: f32) -> f32 {
+        clamp(width, self.min_size.width, self.max_size.width)
+    }
+
+    pub(crate) fn clamp_height(&self, height: f32) -> f32 {
+        clamp(height, self.min_size.height, self.max_size.height)
+    }
+
+    pub(crate) fn width(&self) -> Option<f32> {
+        self.size.width.map(|width| self.clamp_width(width))
+    }
+
+    pub(crate) fn height(&self) -> Option<f32> {
+        self.size.height.map(|height| self.clamp_height(height))
+    }
+}
+
+/// The resolved size constraints, padding and border of a container
+pub(crate) struct ContainerBox {
+    pub(crate) constraints: SizeConstraints,
+    pub(crate) padding: taffy::Rect<f32>,
+    pub(crate) border: taffy::Rect<f32>,
+    /// The total padding and border along each axis
+    pub(crate) padding_border: taffy::Size<f32>,
+}
+
+impl ContainerBox {
+    /// Resolve the container's own sizes. Percentages of padding and border resolve against
+    /// the width of the parent as in CSS.
+    pub(crate) fn resolve(
+        style: &taffy::Style,
+        parent_size: taffy::Size<Option<f32>>,
+        sizing_mode: taffy::SizingMode,
+    ) -> Self {
+        let constraints = match sizing_mode {
+            taffy::SizingMode::InherentSize => SizeConstraints::resolve(style, parent_size),
+            taffy::SizingMode::ContentSize => {
+                SizeConstraints::resolve(&taffy::Style::DEFAULT, parent_size)
+            }
+        };
+        let padding = style
+            .padding
+            .map(|p| resolve_length_percentage(p, parent_size.width));
+        let border = style
+            .border
+            .map(|b| resolve_length_percentage(b, parent_size.width));
+        let padding_border = taffy::Size {
+            width: padding.left + padding.right + border.left + border.right,
+            height: padding.top + padding.bottom + border.top + border.bottom,
+        };
+        Self {
+            constraints,
+            padding,
+            border,
+            padding_border,
+        }
+    }
+}
+
+/// An implementation of CSS block layout which uses Taffy's [`LayoutTree`](taffy::LayoutTree)
+pub(crate) struct BlockAlgorithm;
+
+impl ::taffy::LayoutAlgorithm for BlockAlgorithm {
+    const NAME: &'static str = "BLOCK";
+
+    fn measure_size(
+        tree: &mut impl taffy::LayoutTree,
+        node: taffy::NodeId,
+        known_dimensions: taffy::Size<Option<f32>>,
+        parent_size: taffy::Size<Option<f32>>,
+        available_space: taffy::Size<taffy::AvailableSpace>,
+        sizing_mode: taffy::SizingMode,
+    ) -> taffy::Size<f32> {
+        compute(
+            tree,
+            node,
+            known_dimensions,
+            parent_size,
+            available_space,
+            sizing_mode,
+            taffy::RunMode::ComputeSize,
+        )
+        .size
+    }
+
+    fn perform_layout(
+        tree: &mut impl taffy::LayoutTree,
+        node: taffy::NodeId,
+        known_dimensions: taffy::Size<Option<f32>>,
+        parent_size: taffy::Size<Option<f32>>,
+        available_space: taffy::Size<taffy::AvailableSpace>,
+        sizing_mode: taffy::SizingMode,
+    ) -> taffy::SizeAndBaselines {
+        compute(
+            tree,
+            node,
+            known_dimensions,
+            parent_size,
+            available_space,
+            sizing_mode,
+            taffy::RunMode::PeformLayout,
+        )
+    }
+}
+
+/// An in-flow child of the block container, with its margins resolved
+struct BlockItem {
+    node: taffy::NodeId,
+    order: u32,
+    constraints: SizeConstraints,
+    margin: taffy::Rect<Option<f32>>,
+}
+
+fn compute(
+    tree: &mut impl taffy::LayoutTree,
+    node: taffy::NodeId,
+    known_dimensions: taffy::Size<Option<f32>>,
+    parent_size: taffy::Size<Option<f32>>,
+    available_space: taffy::Size<taffy::AvailableSpace>,
+    sizing_mode: taffy::SizingMode,
+    run_mode: taffy::RunMode,
+) -> taffy::SizeAndBaselines {
+    let style = tree.style(node);
+
+    let ContainerBox {
+        constraints,
+        padding,
+        border,
+        padding_border,
+    } = ContainerBox::resolve(style, parent_size, sizing_mode);
+
+    // Collect in-flow and absolutely positioned children
+    let mut items = Vec::new();
+    let mut absolute_items = Vec::new();
+    let mut hidden_items = Vec::new();
+    for (order, child) in tree.children(node).enumerate() {
+        let child_style = tree.style(child);
+        if child_style.display == taffy::Display::None {
+            hidden_items.push((child, order as u32));
+        } else if child_style.position == taffy::Position::Absolute {
+            absolute_items.push((child, order as u32));
+        } else {
+            items.push(BlockItem {
+                node: child,
+                order: order as u32,
+                constraints: SizeConstraints::resolve(child_style, taffy::Size::NONE),
+                margin: child_style
+                    .margin
+                    .map(|m| resolve_length_percentage_auto(m, None)),
+            });
+        }
+    }
+
+    // Determine the container's width. An auto-width block fills the available space, or
+    // shrinks to fit its children when sized under a min-content or max-content constraint.
+    let width = known_dimensions
+        .width
+        .or(constraints.width())
+        .unwrap_or_else(|| {
+            let width = match available_space.width {
+                taffy::AvailableSpace::Definite(width) => width,
+                intrinsic => {
+                    let content_width = items
+                        .iter()
+                        .map(|item| {
+                            let child_style = tree.style(item.node);
+                            let margin = child_style
+                                .margin
+                                .map(|m| resolve_length_percentage_auto(m, None).unwrap_or(0.0));
+                            let width = item.constraints.width().unwrap_or_else(|| {
+                                let size = tree.measure_child_size(
+                                    item.node,
+                                    taffy::Size::NONE,
+                                    taffy::Size::NONE,
+                                    taffy::Size {
+                                        width: intrinsic,
+                                        height: taffy::AvailableSpace::MaxContent,
+                                    },
+                                    taffy::SizingMode::InherentSize,
+                                );
+                                item.constraints.clamp_width(size.width)
+                            });
+                            width + margin.left + margin.right
+                        })
+                        .fold(0.0, f32::max);
+                    content_width + padding_border.width
+                }
+            };
+            constraints.clamp_width(width)
+        });
+    let content_width = (width - padding_border.width).max(0.0);
+    let known_height = known_dimensions.height.or(constraints.height());
+    let content_height = known_height.map(|height| (height - padding_border.height).max(0.0));
+    let content_size = taffy::Size {
+        width: Some(content_width),
+        height: content_height,
+    };
+
+    // Resolve child sizes now that the container's width is known
+    for item in items.iter_mut() {
+        let child_style = tree.style(item.node);
+        item.constraints = SizeConstraints::resolve(child_style, content_size);
+        item.margin = child_style
+            .margin
+            .map(|m| resolve_length_percentage_auto(m, content_size.width));
+    }
+
+    // Stack the in-flow children vertically, collapsing adjoining margins
+    let mut y = padding.top + border.top;
+    let mut pending_margin: Option<f32> = None;
+    let mut first_baseline = None;
+    for item in items.iter() {
+        let margin_left = item.margin.left.unwrap_or(0.0);
+        let margin_right = item.margin.right.unwrap_or(0.0);
+        let margin_top = item.margin.top.unwrap_or(0.0);
+        let margin_bottom = item.margin.bottom.unwrap_or(0.0);
+
+        // Children without a definite width stretch to fill the container
+        let child_width = item.constraints.width().unwrap_or_else(|| {
+            item.constraints
+                .clamp_width(content_width - margin_left - margin_right)
+        });
+        let child_known_dimensions = taffy::Size {
+            width: Some(child_width),
+            height: item.constraints.height(),
+        };
+        let child_available_space = taffy::Size {
+            width: taffy::AvailableSpace::Definite(child_width),
+            height: content_height
+                .map(taffy::AvailableSpace::Definite)
+                .unwrap_or(available_space.height),
+        };
+        let (child_height, child_baseline) = match run_mode {
+            taffy::RunMode::PeformLayout => {
+                let size_and_baselines = tree.perform_child_layout(
+                    item.node,
+                    child_known_dimensions,
+                    content_size,
+                    child_available_space,
+                    taffy::SizingMode::InherentSize,
+                );
+                (
+                    size_and_baselines.size.height,
+                    size_and_baselines.first_baselines.y,
+                )
+            }
+            _ => {
+                let size = tree.measure_child_size(
+                    item.node,
+                    child_known_dimensions,
+                    content_size,
+                    child_available_space,
+                    taffy::SizingMode::InherentSize,
+                );
+                (size.height, None)
+            }
+        };
+        let child_height = item.constraints.clamp_height(child_height);
+
+        // Auto margins absorb any free space in the inline axis
+        let free_space = (content_width - child_width - margin_left - margin_right).max(0.0);
+        let x_offset = match (item.margin.left, item.margin.right) {
+            (None, None) => free_space / 2.0,
+            (None, Some(_)) => free_space,
+            _ => margin_left,
+        };
+
+        y += match pending_margin {
+            Some(previous_margin_bottom) => collapse_margins(previous_margin_bottom, margin_top),
+            None => margin_top,
+        };
+
+        if matches!(run_mode, taffy::RunMode::PeformLayout) {
+            *tree.layout_mut(item.node) = taffy::Layout {
+                order: item.order,
+                size: taffy::Size {
+                    width: child_width,
+                    height: child_height,
+                },
+                location: taffy::Point {
+                    x: padding.left + border.left + x_offset,
+                    y,
+                },
+            };
+        }
+
+        // The container's first baseline is that of its first in-flow child
+        if pending_margin.is_none() {
+            first_baseline = child_baseline.map(|baseline| y + baseline);
+        }
+
+        y += child_height;
+        pending_margin = Some(margin_bottom);
+    }
+    y += pending_margin.unwrap_or(0.0);
+
+    let height = known_height
+        .unwrap_or_else(|| constraints.clamp_height(y + padding.bottom + border.bottom));
+    let size = taffy::Size { width, height };
+
+    if matches!(run_mode, taffy::RunMode::PeformLayout) {
+        // Absolutely positioned children are placed relative to the padding box
+        let padding_box = taffy::Size {
+            width: width - border.left - border.right,
+            height: height - border.top - border.bottom,
+        };
+        for (child, order) in absolute_items {
+            let child_style = tree.style(child);
+            let area_size = taffy::Size {
+                width: Some(padding_box.width),
+                height: Some(padding_box.height),
+            };
+            let child_constraints = SizeConstraints::resolve(child_style, area_size);
+            let margin = child_style
+                .margin
+                .map(|m| resolve_length_percentage_auto(m, area_size.width).unwrap_or(0.0));
+            // Percentage insets resolve against the padding box along their own axis
+            let inset = taffy::Rect {
+                left: resolve_length_percentage_auto(child_style.inset.left, area_size.width),
+                right: resolve_length_percentage_auto(child_style.inset.right, area_size.width),
+                top: resolve_length_percentage_auto(child_style.inset.top, area_size.height),
+                bottom: resolve_length_percentage_auto(child_style.inset.bottom, area_size.height),
+            };
+
+            // Insets on both sides of an axis define the child's size in that axis
+            let width = child_constraints
+                .width()
+                .or_else(|| match (inset.left, inset.right) {
+                    (Some(left), Some(right)) => Some(child_constraints.clamp_width(
+                        padding_box.width - left - right - margin.left - margin.right,
+                    )),
+                    _ => None,
+                });
+            let height = child_constraints
+                .height()
+                .or_else(|| match (inset.top, inset.bottom) {
+                    (Some(top), Some(bottom)) => Some(child_constraints.clamp_height(
+                        padding_box.height - top - bottom - margin.top - margin.bottom,
+                    )),
+                    _ => None,
+                });
+
+            let child_size = tree
+                .perform_child_layout(
+                    child,
+                    taffy::Size { width, height },
+                    area_size,
+                    taffy::Size {
+                        width: taffy::AvailableSpace::Definite(padding_box.width),
+                        height: taffy::AvailableSpace::Definite(padding_box.height),
+                    },
+                    taffy::SizingMode::InherentSize,
+                )
+                .size;
+
+            let x = match (inset.left, inset.right) {
+                (Some(left), _) => left + margin.left,
+                (None, Some(right)) => padding_box.width - right - margin.right - child_size.width,
+                (None, None) => padding.left + margin.left,
+            };
+            let y = match (inset.top, inset.bottom) {
+                (Some(top), _) => top + margin.top,
+                (None, Some(bottom)) => {
+                    padding_box.height - bottom - margin.bottom - child_size.height
+                }
+                (None, None) => padding.top + margin.top,
+            };
+
+            *tree.layout_mut(child) = taffy::Layout {
+                order,
+                size: child_size,
+                location: taffy::Point {
+                    x: border.left + x,
+                    y: border.top + y,
+                },
+            };
+        }
+
+        for (child, order) in hidden_items {
+            *tree.layout_mut(child) = taffy::Layout {
+                order,
+                ..taffy::NULL_LAYOUT
+            };
+        }
+    }
+
+    taffy::SizeAndBaselines {
+        size,
+        first_baselines: taffy::Point {
+            x: None,
+            y: first_baseline,
+        },
+    }
+}
+
+pub struct Block<'a, Msg, R: Renderer> {
+    container: Container<'a, Msg, R>,
+}
+
+impl<'a, Msg, R: Renderer> Block<'a, Msg, R> {
+    pub fn new() -> Self {
+        Self {
+            container: Container::new(taffy::Style::DEFAULT),
+        }
+    }
+
+    pub fn style(mut self, mut callback: impl FnMut(&mut taffy::Style)) -> Self {
+        callback(&mut self.container.style);
+        self
+    }
+
+    fn algorithm(&self) -> Algorithm {
+        Algorithm::Block
+    }
+}
+
+container_builders!(Block);
+container_widget!(Block);
+
+pub fn block<'a, Msg, R: Renderer>() -> Block<'a, Msg, R> {
+    Block::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::ROOT_NODE_ID;
+    use taffy::style_helpers::{auto, percent, points};
+
+    /// A container whose children are empty leaves, which are as large as their known size
+    struct MockTree {
+        styles: Vec<taffy::Style>,
+        layouts: Vec<taffy::Layout>,
+    }
+
+    impl MockTree {
+        fn new(style: taffy::Style, children: Vec<taffy::Style>) -> Self {
+            let mut styles = vec![style];
+            styles.extend(children);
+            Self {
+                layouts: vec![taffy::NULL_LAYOUT; styles.len()],
+                styles,
+            }
+        }
+
+        /// Lay out the container in the given available space
+        fn layout(&mut self, width: f32, height: f32) -> taffy::Size<f32> {
+            compute(
+                self,
+                ROOT_NODE_ID,
+                taffy::Size::NONE,
+                taffy::Size::NONE,
+                taffy::Size {
+                    width: taffy::AvailableSpace::Definite(width),
+                    height: taffy::AvailableSpace::Definite(height),
+                },
+                taffy::SizingMode::InherentSize,
+                taffy::RunMode::PeformLayout,
+            )
+            .size
+        }
+
+        fn location(&self, child: usize) -> (f32, f32) {
+            let location = self.layouts[child].location;
+            (location.x, location.y)
+        }
+    }
+
+    impl taffy::LayoutTree for MockTree {
+        type ChildIter<'iter>
+            = std::iter::Map<std::ops::Range<usize>, fn(usize) -> taffy::NodeId>
+        where
+            Self: 'iter;
+
+        fn style(&self, node: taffy::NodeId) -> &taffy::Style {
+            &self.styles[usize::from(node)]
+        }
+
+        fn layout_mut(&mut self, node: taffy::NodeId) -> &mut taffy::Layout {
+            &mut self.layouts[usize::from(node)]
+        }
+
+        fn children(&self, node: taffy::NodeId) -> Self::ChildIter<'_> {
+            let children = if node == ROOT_NODE_ID {
+                1..self.styles.len()
+            } else {
+                0..0
+            };
+            children.map(taffy::NodeId::from as fn(usize) -> taffy::NodeId)
+        }
+
+        fn child_count(&self, node: taffy::NodeId) -> usize {
+            self.children(node).len()
+        }
+
+        fn child(&self, _node: taffy::NodeId, index: usize) -> taffy::NodeId {
+            (index + 1).into()
+        }
+
+        fn measure_child_size(
+            &mut self,
+            _child_node_id: taffy::NodeId,
+            known_dimensions: taffy::Size<Option<f32>>,
+            _parent_size: taffy::Size<Option<f32>>,
+            _available_space: taffy::Size<taffy::AvailableSpace>,
+            _sizing_mode: taffy::SizingMode,
+        ) -> taffy::Size<f32> {
+            known_dimensions.unwrap_or(taffy::Size::ZERO)
+        }
+
+        fn perform_child_layout(
+            &mut self,
+            _child_node_id: taffy::NodeId,
+            known_dimensions: taffy::Size<Option<f32>>,
+            _parent_size: taffy::Size<Option<f32>>,
+            _available_space: taffy::Size<taffy::AvailableSpace>,
+            _sizing_mode: taffy::SizingMode,
+        ) -> taffy::SizeAndBaselines {
+            known_dimensions.unwrap_or(taffy::Size::ZERO).into()
+        }
+    }
+
+    fn sized(width: f32, height: f32) -> taffy::Style {
+        taffy::Style {
+            size: taffy::Size {
+                width: points(width),
+                height: points(height),
+            },
+            ..taffy::Style::DEFAULT
+        }
+    }
+
+    fn vertical_margins(top: f32, bottom: f32) -> taffy::Rect<taffy::LengthPercentageAuto> {
+        taffy::Rect {
+            left: points(0.0),
+            right: points(0.0),
+            top: points(top),
+            bottom: points(bottom),
+        }
+    }
+
+    #[test]
+    fn margins_of_the_same_sign_collapse_to_the_largest() {
+        assert_eq!(collapse_margins(10.0, 20.0), 20.0);
+        assert_eq!(collapse_margins(20.0, 10.0), 20.0);
+        assert_eq!(collapse_margins(-10.0, -20.0), -20.0);
+        assert_eq!(collapse_margins(0.0, -5.0), -5.0);
+    }
+
+    #[test]
+    fn margins_of_opposite_signs_are_added() {
+        assert_eq!(collapse_margins(20.0, -5.0), 15.0);
+        assert_eq!(collapse_margins(-20.0, 5.0), -15.0);
+    }
+
+    #[test]
+    fn adjoining_sibling_margins_collapse() {
+        let mut tree = MockTree::new(
+            taffy::Style::DEFAULT,
+            vec![
+                taffy::Style {
+                    margin: vertical_margins(5.0, 20.0),
+                    ..sized(100.0, 10.0)
+                },
+                taffy::Style {
+                    margin: vertical_margins(10.0, 5.0),
+                    ..sized(100.0, 10.0)
+                },
+            ],
+        );
+        let size = tree.layout(100.0, 100.0);
+        assert_eq!(tree.location(1), (0.0, 5.0));
+        assert_eq!(tree.location(2), (0.0, 35.0));
+        assert_eq!(size.height, 50.0);
+    }
+
+    #[test]
+    fn auto_margins_center_a_child() {
+        let mut tree = MockTree::new(
+            taffy::Style::DEFAULT,
+            vec![
+                taffy::Style {
+                    margin: taffy::Rect {
+                        left: auto(),
+                        right: auto(),
+                        top: points(0.0),
+                        bottom: points(0.0),
+                    },
+                    ..sized(40.0, 10.0)
+                },
+                taffy::Style {
+                    margin: taffy::Rect {
+                        left: auto(),
+                        right: points(10.0),
+                        top: points(0.0),
+                        bottom: points(0.0),
+                    },
+                    ..sized(40.0, 10.0)
+                },
+            ],
+        );
+        tree.layout(100.0, 100.0);
+        assert_eq!(tree.location(1), (30.0, 0.0));
+        assert_eq!(tree.location(2), (50.0, 10.0));
+    }
+
+    #[test]
+    fn percentage_insets_resolve_against_their_own_axis() {
+        let absolute = |inset| taffy::Style {
+            position: taffy::Position::Absolute,
+            inset,
+            ..sized(20.0, 20.0)
+        };
+        let mut tree = MockTree::new(
+            sized(200.0, 100.0),
+            vec![
+                absolute(taffy::Rect {
+                    left: percent(0.1),
+                    right: auto(),
+                    top: percent(0.1),
+                    bottom: auto(),
+                }),
+                absolute(taffy::Rect {
+                    left: auto(),
+                    right: percent(0.1),
+                    top: auto(),
+                    bottom: percent(0.1),
+                }),
+            ],
+        );
+        tree.layout(200.0, 100.0);
+        assert_eq!(tree.location(1), (20.0, 10.0));
+        assert_eq!(tree.location(2), (160.0, 70.0));
+    }
+
+    #[test]
+    fn insets_on_both_sides_size_an_absolute_child() {
+        let mut tree = MockTree::new(
+            taffy::Style {
+                padding: taffy::Rect {
+                    left: points(5.0),
+                    right: points(5.0),
+                    top: points(5.0),
+                    bottom: points(5.0),
+                },
+                ..sized(200.0, 100.0)
+            },
+            vec![taffy::Style {
+                position: taffy::Position::Absolute,
+                inset: taffy::Rect {
+                    left: points(10.0),
+                    right: points(30.0),
+                    top: percent(0.25),
+                    bottom: percent(0.25),
+                },
+                ..taffy::Style::DEFAULT
+            }],
+        );
+        tree.layout(200.0, 100.0);
+        assert_eq!(tree.location(1), (10.0, 25.0));
+        let size = tree.layouts[1].size;
+        assert_eq!((size.width, size.height), (160.0, 50.0));
+    }
+}
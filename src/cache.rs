@@ -0,0 +1,274 @@
+//! Layout caches which persist across rebuilds of the widget tree
+//!
+//! iced rebuilds every widget on each `view()`, so the Taffy caches of a container's children are
+//! lost with the container. Containers therefore keep a copy of their children's caches in their
+//! widget [`Tree`] state, which they pick up when they are diffed against it and restore before
+//! the next layout.
+//!
+//! While it is diffed, a container also marks which of its children are dirty, and so must be
+//! measured again:
+//!
+//! - children whose widget type or style changed,
+//! - nested iced_taffy containers with a dirty child (or whose own style changed),
+//! - children wrapped with [`with_content_key`] whose key changed, and
+//! - any other widget, as its layout may depend on content which iced_taffy cannot see.
+//!
+//! A container with a dirty child is dirty itself, so only the path from a changed child up to
+//! the root is laid out again from scratch.
+//!
+//! Since iced_taffy cannot see whether the content of a plain widget such as a `text` or a
+//! `button` changed, a container with any such child is dirty after every `view()`, along with
+//! all of its ancestors, and nothing is saved by caching them. Only containers whose children are
+//! all iced_taffy containers, or are wrapped with [`with_content_key`], are laid out from their
+//! caches. In a large grid of plain widgets, wrap each cell with [`with_content_key`] to benefit.
+
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+use iced_native::event::{self, Event};
+use iced_native::renderer::Renderer;
+use iced_native::widget::{tree, Operation, Tree};
+use iced_native::{layout, mouse, overlay, renderer};
+use iced_native::{Clipboard, Element, Layout, Length, Point, Rectangle, Shell, Size, Widget};
+
+use crate::baseline::{report_first_baseline, take_first_baseline};
+use crate::container::ContainerState;
+use crate::tree::taffy;
+use crate::z_index::ZIndexState;
+
+/// The layout cache of a container, shared between its widget [`Tree`] state and the container
+/// built by the latest `view()`
+pub(crate) type SharedLayoutCache = Rc<RefCell<LayoutCache>>;
+
+/// The cached layouts of a container's children, in the same order as the children
+#[derive(Debug, Default)]
+pub(crate) struct LayoutCache {
+    /// The style of the container when the cache was stored
+    pub(crate) style: taffy::Style,
+    pub(crate) children: Vec<CachedChild>,
+    /// Whether the container or any of its children changed since the cache was stored
+    pub(crate) dirty: bool,
+}
+
+/// The Taffy cache and iced layouts of a single child
+#[derive(Debug)]
+pub(crate) struct CachedChild {
+    /// The type of the child's widget when the cache was stored
+    pub(crate) tag: tree::Tag,
+    /// The style which the child was given by its container when the cache was stored
+    pub(crate) explicit_style: taffy::Style,
+    /// The resolved style of the child when the cache was stored
+    pub(crate) style: taffy::Style,
+    pub(crate) cache: taffy::Cache,
+    pub(crate) iced_child_layouts: Vec<layout::Node>,
+    /// Whether the child changed since the cache was stored
+    pub(crate) dirty: bool,
+}
+
+impl CachedChild {
+    /// Whether the cache can be reused for a child with the given widget type and style
+    pub(crate) fn matches(&self, tag: tree::Tag, style: &taffy::Style) -> bool {
+        !self.dirty && is_tracked(tag) && self.tag == tag && &self.style == style
+    }
+}
+
+/// Whether iced_taffy can tell from the widget [`Tree`] of a widget of this type whether its
+/// layout changed. The caches of other widgets are never reused, whether or not they were marked
+/// dirty, as their layout may depend on content which iced_taffy cannot see.
+pub(crate) fn is_tracked(tag: tree::Tag) -> bool {
+    tag == tree::Tag::of::<ContainerState>()
+        || tag == tree::Tag::of::<ContentKeyState>()
+        || tag == tree::Tag::of::<ZIndexState>()
+}
+
+/// Whether the widget whose (already diffed) state is `tree` may have a different layout from the
+/// one cached by its container
+pub(crate) fn is_dirty(tree: &Tree) -> bool {
+    if tree.tag == tree::Tag::of::<ContainerState>() {
+        tree.state
+            .downcast_ref::<ContainerState>()
+            .layout_cache
+            .borrow()
+            .dirty
+    } else if tree.tag == tree::Tag::of::<ZIndexState>() {
+        is_dirty(&tree.children[0])
+    } else if tree.tag == tree::Tag::of::<ContentKeyState>() {
+        // A keyed container is still dirty if its own content changed
+        let content = &tree.children[0];
+        tree.state.downcast_ref::<ContentKeyState>().changed
+            || (content.tag == tree::Tag::of::<ContainerState>() && is_dirty(content))
+    } else {
+        true
+    }
+}
+
+/// The state of a [`WithContentKey`]
+struct ContentKeyState {
+    key: u64,
+    /// Whether the key changed in the latest diff
+    changed: bool,
+}
+
+/// A widget whose layout is only recomputed by iced_taffy containers when its content key changes
+pub struct WithContentKey<'a, Msg, R: Renderer> {
+    content: Element<'a, Msg, R>,
+    key: u64,
+}
+
+impl<'a, Msg, R: Renderer> WithContentKey<'a, Msg, R> {
+    /// Creates a [`WithContentKey`] whose layout is reused for as long as `key` is unchanged
+    pub fn new(content: impl Into<Element<'a, Msg, R>>, key: impl Hash) -> Self {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        Self {
+            content: content.into(),
+            key: hasher.finish(),
+        }
+    }
+}
+
+/// Have iced_taffy containers reuse the cached layout of `content` for as long as `key` is
+/// unchanged.
+///
+/// The key should change whenever anything which affects the size of `content` does, for example
+/// the string of a `text` widget. Without a key, containers measure ordinary widgets again after
+/// every `view()`.
+pub fn with_content_key<'a, Msg, R: Renderer>(
+    content: impl Into<Element<'a, Msg, R>>,
+    key: impl Hash,
+) -> WithContentKey<'a, Msg, R> {
+    WithContentKey::new(content, key)
+}
+
+impl<'a, Msg, R: Renderer> Widget<Msg, R> for WithContentKey<'a, Msg, R> {
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<ContentKeyState>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(ContentKeyState {
+            key: self.key,
+            changed: true,
+        })
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(self.content.as_widget())]
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        let state = tree.state.downcast_mut::<ContentKeyState>();
+        state.changed = state.key != self.key;
+        state.key = self.key;
+        tree.diff_children(&[self.content.as_widget()])
+    }
+
+    fn width(&self) -> Length {
+        self.content.as_widget().width()
+    }
+
+    fn height(&self) -> Length {
+        self.content.as_widget().height()
+    }
+
+    fn measure(&mut self, renderer: &R, limits: &layout::Limits) -> Size {
+        self.content.as_widget_mut().measure(renderer, limits)
+    }
+
+    fn layout(&mut self, renderer: &R, limits: &layout::Limits) -> layout::Node {
+        let node = self.content.as_widget_mut().layout(renderer, limits);
+        let first_baseline = take_first_baseline(self.content.as_widget());
+        report_first_baseline(&*self, first_baseline);
+        node
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &R,
+        operation: &mut dyn Operation<Msg>,
+    ) {
+        self.content
+            .as_widget()
+            .operate(&mut tree.children[0], layout, renderer, operation)
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &R,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Msg>,
+    ) -> event::Status {
+        self.content.as_widget_mut().on_event(
+            &mut tree.children[0],
+            event,
+            layout,
+            cursor_position,
+            renderer,
+            clipboard,
+            shell,
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+        renderer: &R,
+    ) -> mouse::Interaction {
+        self.content.as_widget().mouse_interaction(
+            &tree.children[0],
+            layout,
+            cursor_position,
+            viewport,
+            renderer,
+        )
+    }
+
+    fn draw(
+        &mut self,
+        tree: &Tree,
+        renderer: &mut R,
+        theme: &R::Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+    ) {
+        self.content.as_widget_mut().draw(
+            &tree.children[0],
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor_position,
+            viewport,
+        )
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'_>,
+        renderer: &R,
+    ) -> Option<overlay::Element<'b, Msg, R>> {
+        self.content
+            .as_widget_mut()
+            .overlay(&mut tree.children[0], layout, renderer)
+    }
+}
+
+impl<'a, Msg: 'a, R: Renderer + 'a> From<WithContentKey<'a, Msg, R>> for Element<'a, Msg, R> {
+    fn from(with_content_key: WithContentKey<'a, Msg, R>) -> Self {
+        Self::new(with_content_key)
+    }
+}
@@ -0,0 +1,1361 @@
+//! Shared implementation of the widgets which lay out their children using Taffy
+
+use std::cell::Cell;
+
+use iced_native::event::{self, Event};
+use iced_native::renderer::Renderer;
+use iced_native::widget::{tree, Operation, Tree};
+use iced_native::{layout, mouse, overlay, renderer, touch};
+use iced_native::{Clipboard, Element, Layout, Length, Point, Rectangle, Shell, Size};
+
+use crate::block::BlockAlgorithm;
+use crate::cache::{is_tracked, CachedChild, LayoutCache, SharedLayoutCache};
+use crate::masonry::MasonryAlgorithm;
+use crate::measure::{take_intrinsic_query, IntrinsicQuery};
+use crate::overlay::OverlayGroup;
+use crate::tree::{
+    dimension_to_length, f32_to_opt, length_to_dimension, taffy, ChildWidget, TaffyChild,
+    TaffyLayoutTree, ROOT_NODE_ID,
+};
+use crate::z_index::z_index;
+use ::taffy::LayoutAlgorithm;
+
+/// The Taffy layout algorithm used to position a container's children
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Algorithm {
+    Grid,
+    Flex,
+    Block,
+    Masonry,
+    /// Lays out nothing: the container and all of its children are given a zero size, and the
+    /// children are neither drawn nor sent events
+    None,
+}
+
+impl Algorithm {
+    fn measure_size(
+        self,
+        tree: &mut impl taffy::LayoutTree,
+        known_dimensions: taffy::Size<Option<f32>>,
+        parent_size: taffy::Size<Option<f32>>,
+        available_space: taffy::Size<taffy::AvailableSpace>,
+        sizing_mode: taffy::SizingMode,
+    ) -> taffy::Size<f32> {
+        match self {
+            Algorithm::Grid => taffy::CssGridAlgorithm::measure_size(
+                tree,
+                ROOT_NODE_ID,
+                known_dimensions,
+                parent_size,
+                available_space,
+                sizing_mode,
+            ),
+            Algorithm::Flex => taffy::FlexboxAlgorithm::measure_size(
+                tree,
+                ROOT_NODE_ID,
+                known_dimensions,
+                parent_size,
+                available_space,
+                sizing_mode,
+            ),
+            Algorithm::Block => BlockAlgorithm::measure_size(
+                tree,
+                ROOT_NODE_ID,
+                known_dimensions,
+                parent_size,
+                available_space,
+                sizing_mode,
+            ),
+            Algorithm::Masonry => MasonryAlgorithm::measure_size(
+                tree,
+                ROOT_NODE_ID,
+                known_dimensions,
+                parent_size,
+                available_space,
+                sizing_mode,
+            ),
+            Algorithm::None => taffy::Size::ZERO,
+        }
+    }
+
+    fn perform_layout(
+        self,
+        tree: &mut impl taffy::LayoutTree,
+        known_dimensions: taffy::Size<Option<f32>>,
+        parent_size: taffy::Size<Option<f32>>,
+        available_space: taffy::Size<taffy::AvailableSpace>,
+        sizing_mode: taffy::SizingMode,
+    ) -> taffy::SizeAndBaselines {
+        match self {
+            Algorithm::Grid => taffy::CssGridAlgorithm::perform_layout(
+                tree,
+                ROOT_NODE_ID,
+                known_dimensions,
+                parent_size,
+                available_space,
+                sizing_mode,
+            ),
+            Algorithm::Flex => taffy::FlexboxAlgorithm::perform_layout(
+                tree,
+                ROOT_NODE_ID,
+                known_dimensions,
+                parent_size,
+                available_space,
+                sizing_mode,
+            ),
+            Algorithm::Block => BlockAlgorithm::perform_layout(
+                tree,
+                ROOT_NODE_ID,
+                known_dimensions,
+                parent_size,
+                available_space,
+                sizing_mode,
+            ),
+            Algorithm::Masonry => MasonryAlgorithm::perform_layout(
+                tree,
+                ROOT_NODE_ID,
+                known_dimensions,
+                parent_size,
+                available_space,
+                sizing_mode,
+            ),
+            Algorithm::None => {
+                for order in 0..tree.child_count(ROOT_NODE_ID) {
+                    let child = tree.child(ROOT_NODE_ID, order);
+                    *tree.layout_mut(child) = taffy::Layout {
+                        order: order as u32,
+                        ..taffy::NULL_LAYOUT
+                    };
+                }
+                taffy::SizeAndBaselines {
+                    size: taffy::Size::ZERO,
+                    first_baselines: taffy::Point::NONE,
+                }
+            }
+        }
+    }
+}
+
+/// Fill in the parts of a child's style which were left at their defaults from the iced
+/// [`Length`]s reported by its widget, given the algorithm and flex direction of its container.
+///
+/// `Fixed` lengths become definite sizes, and `Fill` and `FillPortion` make the child stretch
+/// to fill its grid area, or grow in proportion to its portion along a flex line. Parts of the
+/// style which were set explicitly are kept.
+pub(crate) fn apply_child_lengths(
+    style: &mut taffy::Style,
+    width: Length,
+    height: Length,
+    algorithm: Algorithm,
+    flex_direction: taffy::FlexDirection,
+) {
+    if let (Length::Fixed(points), taffy::Dimension::Auto) = (width, style.size.width) {
+        style.size.width = taffy::Dimension::Points(points);
+    }
+    if let (Length::Fixed(points), taffy::Dimension::Auto) = (height, style.size.height) {
+        style.size.height = taffy::Dimension::Points(points);
+    }
+
+    let main_axis_is_row = matches!(
+        flex_direction,
+        taffy::FlexDirection::Row | taffy::FlexDirection::RowReverse
+    );
+    for (length, is_row) in [(width, true), (height, false)] {
+        let portion = match length {
+            Length::Fill => 1,
+            Length::FillPortion(portion) => portion,
+            Length::Shrink | Length::Fixed(_) => continue,
+        };
+        match algorithm {
+            Algorithm::Grid => {
+                let alignment = if is_row {
+                    &mut style.justify_self
+                } else {
+                    &mut style.align_self
+                };
+                alignment.get_or_insert(taffy::AlignSelf::Stretch);
+            }
+            Algorithm::Flex if is_row == main_axis_is_row => {
+                // Like iced's own rows and columns, share the free space out in proportion to
+                // the portions, regardless of the children's content sizes
+                if style.flex_grow == 0.0 {
+                    style.flex_grow = portion as f32;
+                }
+                if style.flex_basis == taffy::Dimension::Auto {
+                    style.flex_basis = taffy::Dimension::Points(0.0);
+                }
+            }
+            Algorithm::Flex => {
+                style.align_self.get_or_insert(taffy::AlignSelf::Stretch);
+            }
+            // Block and masonry layout already stretch children across their width
+            Algorithm::Block | Algorithm::Masonry | Algorithm::None => {}
+        }
+    }
+}
+
+/// The iced [`Length`] reported for a container of the given size style, whose size was last set
+/// from `length`. The length round-trips unless the style was changed since, as Taffy cannot
+/// represent a [`Length::FillPortion`].
+fn reported_length(length: Length, dimension: taffy::Dimension) -> Length {
+    if length_to_dimension(length) == dimension {
+        length
+    } else {
+        dimension_to_length(dimension)
+    }
+}
+
+/// Round the position and size of a layout along one axis to the pixel grid.
+///
+/// Both edges are rounded, rather than the position and size independently, so that adjacent
+/// layouts which share an edge still share it after rounding.
+fn snap_edges(start: f32, size: f32, scale_factor: f32) -> (f32, f32) {
+    let snapped_start = snap(start, scale_factor);
+    let snapped_end = snap(start + size, scale_factor);
+    (snapped_start, snapped_end - snapped_start)
+}
+
+/// Round a position to the physical pixels of the given scale factor
+fn snap(value: f32, scale_factor: f32) -> f32 {
+    (value * scale_factor).round() / scale_factor
+}
+
+/// Snap the edges of `node` and of all of its descendants to the physical pixels of the given
+/// scale factor.
+///
+/// `origin` is the position of the node's parent within the outermost container before snapping,
+/// and `snapped_origin` its position after. Each edge is rounded at its position within the
+/// outermost container, rather than within its parent, so that rounding errors do not accumulate.
+fn snap_node(
+    node: layout::Node,
+    origin: Point,
+    snapped_origin: Point,
+    scale_factor: f32,
+) -> layout::Node {
+    let bounds = node.bounds();
+    let position = Point::new(origin.x + bounds.x, origin.y + bounds.y);
+    let (x, width) = snap_edges(position.x, bounds.width, scale_factor);
+    let (y, height) = snap_edges(position.y, bounds.height, scale_factor);
+    let snapped_position = Point::new(x, y);
+
+    let children = node
+        .into_children()
+        .into_iter()
+        .map(|child| snap_node(child, position, snapped_position, scale_factor))
+        .collect();
+    let mut snapped = layout::Node::with_children(Size::new(width, height), children);
+    snapped.move_to(Point::new(x - snapped_origin.x, y - snapped_origin.y));
+    snapped
+}
+
+thread_local! {
+    /// The number of Taffy-based containers which are currently being measured or laid out
+    static NESTING_DEPTH: Cell<usize> = Cell::new(0);
+    /// The number of times an outermost container started to be measured or laid out
+    static PASS: Cell<u64> = Cell::new(0);
+}
+
+/// Marks a container as being measured or laid out, for as long as it is alive
+struct NestingGuard;
+
+impl NestingGuard {
+    /// Enter a container, returning whether it is the outermost one being measured or laid out.
+    /// Entering an outermost container starts a new pass.
+    fn enter() -> (Self, bool) {
+        let depth = NESTING_DEPTH.with(|depth| depth.replace(depth.get() + 1));
+        if depth == 0 {
+            PASS.with(|pass| pass.set(pass.get() + 1));
+        }
+        (NestingGuard, depth == 0)
+    }
+
+    /// The pass which the containers currently being measured or laid out belong to
+    fn pass() -> u64 {
+        PASS.with(Cell::get)
+    }
+}
+
+impl Drop for NestingGuard {
+    fn drop(&mut self) {
+        NESTING_DEPTH.with(|depth| depth.set(depth.get() - 1));
+    }
+}
+
+/// Whether an event only concerns the children under the cursor (or a finger), and so is only
+/// sent to them.
+///
+/// Presses and releases are not hit-tested: widgets rely on seeing presses outside of their
+/// bounds, for example to unfocus a text input or to close a pick list.
+fn is_hit_tested_event(event: &Event) -> bool {
+    matches!(
+        event,
+        Event::Mouse(mouse::Event::CursorMoved { .. })
+            | Event::Mouse(mouse::Event::WheelScrolled { .. })
+            | Event::Touch(touch::Event::FingerMoved { .. })
+    )
+}
+
+/// Whether an event starts an interaction which the child capturing it should own until it ends
+fn is_press_event(event: &Event) -> bool {
+    matches!(
+        event,
+        Event::Mouse(mouse::Event::ButtonPressed(_))
+            | Event::Touch(touch::Event::FingerPressed { .. })
+    )
+}
+
+/// Whether an event ends the interaction started by a press event
+fn is_release_event(event: &Event) -> bool {
+    matches!(
+        event,
+        Event::Mouse(mouse::Event::ButtonReleased(_))
+            | Event::Touch(touch::Event::FingerLifted { .. })
+            | Event::Touch(touch::Event::FingerLost { .. })
+    )
+}
+
+/// Whether a child with the given bounds can be seen within the viewport. Children which only
+/// touch its edges are kept, so that empty children on the edges are still drawn.
+fn is_in_viewport(bounds: Rectangle, viewport: &Rectangle) -> bool {
+    bounds.x <= viewport.x + viewport.width
+        && bounds.x + bounds.width >= viewport.x
+        && bounds.y <= viewport.y + viewport.height
+        && bounds.y + bounds.height >= viewport.y
+}
+
+/// The inputs of a Taffy layout algorithm, translated from iced [`Limits`](layout::Limits)
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TaffyInputs {
+    known_dimensions: taffy::Size<Option<f32>>,
+    parent_size: taffy::Size<Option<f32>>,
+    available_space: taffy::Size<taffy::AvailableSpace>,
+    sizing_mode: taffy::SizingMode,
+    /// The bounds which the size of the container must lie within
+    min_size: taffy::Size<f32>,
+    max_size: taffy::Size<f32>,
+}
+
+impl TaffyInputs {
+    fn new(limits: &layout::Limits) -> Self {
+        let mut known_dimensions = taffy::Size::NONE;
+        if limits.min().height < f32::INFINITY && limits.min().height == limits.max().height {
+            known_dimensions.height = Some(limits.min().height);
+        }
+        if limits.min().width < f32::INFINITY && limits.min().width == limits.max().width {
+            known_dimensions.width = Some(limits.min().width);
+        }
+        let parent_size = taffy::Size {
+            width: f32_to_opt(limits.max().width),
+            height: f32_to_opt(limits.max().height),
+        };
+        let available_space = parent_size.map(|s| s.into());
+
+        Self {
+            known_dimensions,
+            parent_size,
+            available_space,
+            sizing_mode: taffy::SizingMode::InherentSize,
+            min_size: taffy::Size {
+                width: limits.min().width,
+                height: limits.min().height,
+            },
+            max_size: taffy::Size {
+                width: limits.max().width,
+                height: limits.max().height,
+            },
+        }
+    }
+
+    /// Use the constraints that a parent container placed on this container directly, rather than
+    /// the limits that they were translated into
+    fn from_query(query: IntrinsicQuery) -> Self {
+        Self {
+            known_dimensions: query.known_dimensions,
+            parent_size: query.parent_size,
+            available_space: query.available_space,
+            sizing_mode: query.sizing_mode,
+            min_size: taffy::Size::ZERO,
+            max_size: taffy::Size {
+                width: f32::INFINITY,
+                height: f32::INFINITY,
+            },
+        }
+    }
+
+    /// Taffy has no notion of a minimum or maximum size for the root node beyond its style, so if
+    /// the algorithm produced a `size` outside of the limits, this returns the known dimensions
+    /// to run it again with so that the result fits them.
+    fn clamped_known_dimensions(&self, size: taffy::Size<f32>) -> Option<taffy::Size<Option<f32>>> {
+        let clamp = |known: Option<f32>, size: f32, min: f32, max: f32| match known {
+            Some(known) => Some(known),
+            None if size < min => Some(min),
+            None if size > max => Some(max),
+            None => None,
+        };
+        let known_dimensions = taffy::Size {
+            width: clamp(
+                self.known_dimensions.width,
+                size.width,
+                self.min_size.width,
+                self.max_size.width,
+            ),
+            height: clamp(
+                self.known_dimensions.height,
+                size.height,
+                self.min_size.height,
+                self.max_size.height,
+            ),
+        };
+        (known_dimensions != self.known_dimensions).then_some(known_dimensions)
+    }
+}
+
+/// What the memoized results of a container depend on.
+///
+/// Results are only reused within the pass in which they were computed, as a container is laid
+/// out again with the same inputs when one of its descendants invalidates the layout, and its
+/// descendants must then be laid out again too.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct MemoKey {
+    pass: u64,
+    algorithm: Algorithm,
+    inputs: TaffyInputs,
+}
+
+/// The widget [`Tree`] state of every Taffy-based widget
+#[derive(Default)]
+pub(crate) struct ContainerState {
+    pub(crate) layout_cache: SharedLayoutCache,
+    /// The child which captured the latest button press or touch, which keeps receiving cursor
+    /// events wherever the cursor is (for example while dragging) until it is released
+    pointer_owner: Option<usize>,
+    /// The child which the cursor was over, which is sent the cursor event that moves the cursor
+    /// away from it
+    hovered: Option<usize>,
+}
+
+impl ContainerState {
+    /// Keep track of the pointer owner and the hovered child when the children are rearranged,
+    /// given the new index of each old index which is kept
+    pub(crate) fn remap_children(&mut self, new_index: impl Fn(usize) -> Option<usize>) {
+        self.pointer_owner = self.pointer_owner.and_then(&new_index);
+        self.hovered = self.hovered.and_then(&new_index);
+    }
+}
+
+/// The style and children of a Taffy-based widget.
+///
+/// [`Grid`](crate::Grid), [`Flex`](crate::Flex), [`Block`](crate::Block) and
+/// [`TaffyContainer`](crate::TaffyContainer) wrap this type and forward their
+/// [`Widget`](iced_native::Widget) implementations to it, passing the [`Algorithm`] they use.
+pub(crate) struct Container<'a, Msg, R: Renderer> {
+    pub(crate) style: taffy::Style,
+    /// The iced [`Length`]s which the size of the container was last set from, which are reported
+    /// to parent iced widgets for as long as the size style still matches them
+    lengths: taffy::Size<Length>,
+    pub(crate) children: Vec<TaffyChild<'a, Msg, R>>,
+    /// The scale factor whose physical pixels the layouts of the descendants are snapped to, if
+    /// any.
+    ///
+    /// Edges are rounded rather than sizes, so adjacent children tile without gaps or overlaps.
+    /// Each edge is rounded at its position within the outermost container, which snaps all of
+    /// its descendants (including nested containers, whose own setting is not used) once they
+    /// have been laid out. The outermost container is assumed to be placed on the pixel grid by
+    /// its parent, and its measured size is snapped too so that it matches its laid out size.
+    pub(crate) pixel_snapping: Option<f32>,
+    /// The algorithm and flex direction which the children's styles were last resolved for
+    resolved_for: Option<(Algorithm, taffy::FlexDirection)>,
+    /// The layout cache in the container's widget `Tree` state, picked up in `diff` and not yet
+    /// restored into the children
+    pending_cache: Cell<Option<SharedLayoutCache>>,
+    /// The layout cache which the children's caches are stored back into after each layout
+    layout_cache: Option<SharedLayoutCache>,
+    /// The pass, algorithm and inputs of the latest measure, and the resulting size
+    measured: Option<(MemoKey, Size)>,
+    /// The pass, algorithm and inputs of the latest layout, and the resulting node and first
+    /// baseline
+    laid_out: Option<(MemoKey, layout::Node, Option<f32>)>,
+    /// The pass of the latest layout, until the children's Taffy caches are next cleared
+    cache_pass: Option<u64>,
+}
+
+impl<'a, Msg, R: Renderer> Container<'a, Msg, R> {
+    pub(crate) fn new(style: taffy::Style) -> Self {
+        Self {
+            // Containers fill their parent unless they are given another size
+            style: taffy::Style {
+                size: taffy::Size {
+                    width: taffy::Dimension::Percent(1.0),
+                    height: taffy::Dimension::Percent(1.0),
+                },
+                ..style
+            },
+            lengths: taffy::Size {
+                width: Length::Fill,
+                height: Length::Fill,
+            },
+            children: vec![],
+            pixel_snapping: Some(1.0),
+            resolved_for: None,
+            pending_cache: Cell::new(None),
+            layout_cache: None,
+            measured: None,
+            laid_out: None,
+            cache_pass: None,
+        }
+    }
+
+    /// The width reported to parent iced widgets, derived from the container's style
+    pub(crate) fn width(&self) -> Length {
+        reported_length(self.lengths.width, self.style.size.width)
+    }
+
+    /// The height reported to parent iced widgets, derived from the container's style
+    pub(crate) fn height(&self) -> Length {
+        reported_length(self.lengths.height, self.style.size.height)
+    }
+
+    /// Sets the width of the container from an iced [`Length`]
+    pub(crate) fn set_width(&mut self, width: Length) {
+        self.style.size.width = length_to_dimension(width);
+        self.lengths.width = width;
+    }
+
+    /// Sets the height of the container from an iced [`Length`]
+    pub(crate) fn set_height(&mut self, height: Length) {
+        self.style.size.height = length_to_dimension(height);
+        self.lengths.height = height;
+    }
+
+    pub(crate) fn push(&mut self, element: Element<'a, Msg, R>, style: taffy::Style) {
+        self.children
+            .push(TaffyChild::new(ChildWidget::Element(element), style));
+        self.resolved_for = None;
+        self.measured = None;
+        self.laid_out = None;
+    }
+
+    /// Resolve the style of each child from its explicit style and the iced [`Length`]s of its
+    /// widget. This is only redone when the algorithm or the flex direction changes.
+    fn resolve_child_styles(&mut self, algorithm: Algorithm) {
+        let key = (algorithm, self.style.flex_direction);
+        if self.resolved_for == Some(key) {
+            return;
+        }
+        for child in self.children.iter_mut() {
+            // A subgrid's placement style is always used as it is
+            if let ChildWidget::Element(element) = &child.element {
+                let mut style = child.explicit_style.clone();
+                let widget = element.as_widget();
+                apply_child_lengths(
+                    &mut style,
+                    widget.width(),
+                    widget.height(),
+                    algorithm,
+                    key.1,
+                );
+                child.style = style;
+            }
+        }
+        self.resolved_for = Some(key);
+    }
+
+    pub(crate) fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<ContainerState>()
+    }
+
+    pub(crate) fn state(&self) -> tree::State {
+        tree::State::new(ContainerState::default())
+    }
+
+    pub(crate) fn children(&self) -> Vec<Tree> {
+        self.children
+            .iter()
+            .map(|child| Tree::new(child.element.as_widget()))
+            .collect()
+    }
+
+    pub(crate) fn diff(&self, tree: &mut Tree) {
+        // The pointer owner and the hovered child are stored by index, which may now refer to
+        // another child
+        let rearranged = tree.children.len() != self.children.len()
+            || tree
+                .children
+                .iter()
+                .zip(&self.children)
+                .any(|(tree, child)| tree.tag != child.element.as_widget().tag());
+        tree.diff_children(
+            &self
+                .children
+                .iter()
+                .map(|child| child.element.as_widget())
+                .collect::<Vec<_>>(),
+        );
+        if tree.tag == self.tag() {
+            let state = tree.state.downcast_mut::<ContainerState>();
+            if rearranged {
+                state.pointer_owner = None;
+                state.hovered = None;
+            }
+            let layout_cache = state.layout_cache.clone();
+            self.mark_dirty(&mut layout_cache.borrow_mut(), &tree.children);
+            self.pending_cache.set(Some(layout_cache));
+        }
+    }
+
+    /// Mark the cached children which changed since the cache was stored as dirty, given their
+    /// diffed widget `Tree`s, and the whole container as dirty if any did.
+    ///
+    /// The marks are kept until the cache is next stored, so that changes are not lost if the
+    /// container is diffed again before it is laid out.
+    fn mark_dirty(&self, layout_cache: &mut LayoutCache, trees: &[Tree]) {
+        let mut dirty =
+            layout_cache.style != self.style || layout_cache.children.len() != self.children.len();
+        for ((child, cached), tree) in self
+            .children
+            .iter()
+            .zip(layout_cache.children.iter_mut())
+            .zip(trees)
+        {
+            cached.dirty |= cached.tag != tree.tag
+                || cached.explicit_style != child.explicit_style
+                || crate::cache::is_dirty(tree);
+            dirty |= cached.dirty;
+        }
+        layout_cache.dirty |= dirty;
+    }
+
+    /// Restore the caches of the children (and of the items of subgrids) from the layout cache
+    /// picked up in `diff`, if they are not dirty and still have the same widget type and style.
+    ///
+    /// This is only done once for each container built by `view()`, as the children's own caches
+    /// are kept up to date after that.
+    fn restore_layout_cache(&mut self) {
+        let Some(layout_cache) = self.pending_cache.get_mut().take() else {
+            return;
+        };
+        // The layouts are cloned rather than taken, as the cache is only stored back if the
+        // container is laid out, and not if it is only measured
+        for (child, cached) in self
+            .children
+            .iter_mut()
+            .zip(layout_cache.borrow().children.iter())
+        {
+            if cached.matches(child.element.as_widget().tag(), &child.style) {
+                child.cache = cached.cache.clone();
+                child.iced_child_layouts = cached.iced_child_layouts.clone();
+            }
+        }
+        for child in self.children.iter_mut() {
+            if let ChildWidget::Subgrid(subgrid) = &mut child.element {
+                subgrid.container.restore_layout_cache();
+            }
+        }
+        self.layout_cache = Some(layout_cache);
+    }
+
+    /// Forget the Taffy caches of the children (and of the items of subgrids) if the container
+    /// was already laid out in an earlier pass. It is then being laid out again without having
+    /// been rebuilt by `view()`, because a descendant invalidated the layout, so the caches may be
+    /// stale.
+    fn start_pass(&mut self, pass: u64) {
+        if self
+            .cache_pass
+            .map_or(false, |cache_pass| cache_pass != pass)
+        {
+            self.clear_child_caches();
+            self.cache_pass = None;
+        }
+    }
+
+    fn clear_child_caches(&mut self) {
+        for child in self.children.iter_mut() {
+            child.cache = taffy::Cache::new();
+            if let ChildWidget::Subgrid(subgrid) = &mut child.element {
+                subgrid.container.clear_child_caches();
+            }
+        }
+    }
+
+    /// Store the caches of the children (and of the items of subgrids) back into the layout cache.
+    ///
+    /// Only the caches of children which can be reused are copied. The others are never restored,
+    /// so only what is needed to tell whether they changed is stored.
+    fn store_layout_cache(&self) {
+        if let Some(layout_cache) = &self.layout_cache {
+            let mut layout_cache = layout_cache.borrow_mut();
+            layout_cache.style = self.style.clone();
+            layout_cache.children = self
+                .children
+                .iter()
+                .map(|child| {
+                    let tag = child.element.as_widget().tag();
+                    let (cache, iced_child_layouts) = if is_tracked(tag) {
+                        (child.cache.clone(), child.iced_child_layouts.clone())
+                    } else {
+                        (taffy::Cache::new(), Vec::new())
+                    };
+                    CachedChild {
+                        tag,
+                        explicit_style: child.explicit_style.clone(),
+                        style: child.style.clone(),
+                        cache,
+                        iced_child_layouts,
+                        dirty: false,
+                    }
+                })
+                .collect();
+            layout_cache.dirty = false;
+        }
+        for child in self.children.iter() {
+            if let ChildWidget::Subgrid(subgrid) = &child.element {
+                subgrid.container.store_layout_cache();
+            }
+        }
+    }
+
+    pub(crate) fn measure(
+        &mut self,
+        algorithm: Algorithm,
+        renderer: &R,
+        limits: &layout::Limits,
+    ) -> Size {
+        let inputs = match take_intrinsic_query(limits) {
+            Some(query) => TaffyInputs::from_query(query),
+            None => TaffyInputs::new(limits),
+        };
+        let (_guard, is_outermost) = NestingGuard::enter();
+        let key = MemoKey {
+            pass: NestingGuard::pass(),
+            algorithm,
+            inputs,
+        };
+        // Parents often measure a child with the same constraints that they then lay it out with,
+        // or measure it several times while sizing their tracks
+        match (&self.measured, &self.laid_out) {
+            (Some((memo_key, size)), _) if *memo_key == key => return *size,
+            (_, Some((memo_key, node, _))) if *memo_key == key => return node.size(),
+            _ => {}
+        }
+
+        self.start_pass(key.pass);
+        self.resolve_child_styles(algorithm);
+        self.restore_layout_cache();
+        let mut node_ref = TaffyLayoutTree::new(&self.style, &mut self.children, renderer);
+        let sizing_mode = inputs.sizing_mode;
+
+        let mut size = algorithm.measure_size(
+            &mut node_ref,
+            inputs.known_dimensions,
+            inputs.parent_size,
+            inputs.available_space,
+            sizing_mode,
+        );
+        if let Some(known_dimensions) = inputs.clamped_known_dimensions(size) {
+            size = algorithm.measure_size(
+                &mut node_ref,
+                known_dimensions,
+                inputs.parent_size,
+                inputs.available_space,
+                sizing_mode,
+            );
+        }
+
+        // The outermost container is given the same size as when it is laid out and snapped
+        let size = match (is_outermost, self.pixel_snapping) {
+            (true, Some(scale_factor)) => Size {
+                width: snap(size.width, scale_factor),
+                height: snap(size.height, scale_factor),
+            },
+            _ => Size {
+                width: size.width,
+                height: size.height,
+            },
+        };
+        self.measured = Some((key, size));
+        size
+    }
+
+    pub(crate) fn layout(
+        &mut self,
+        algorithm: Algorithm,
+        renderer: &R,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        let inputs = match take_intrinsic_query(limits) {
+            Some(query) => TaffyInputs::from_query(query),
+            None => TaffyInputs::new(limits),
+        };
+        // Only the outermost container snaps, once all of its descendants have been laid out
+        let (_guard, is_outermost) = NestingGuard::enter();
+        let key = MemoKey {
+            pass: NestingGuard::pass(),
+            algorithm,
+            inputs,
+        };
+        if let Some((memo_key, node, _)) = &self.laid_out {
+            if *memo_key == key {
+                return node.clone();
+            }
+        }
+
+        self.start_pass(key.pass);
+        self.resolve_child_styles(algorithm);
+        self.restore_layout_cache();
+        let mut node_ref = TaffyLayoutTree::new(&self.style, &mut self.children, renderer);
+        let sizing_mode = inputs.sizing_mode;
+
+        let mut size_and_baselines = algorithm.perform_layout(
+            &mut node_ref,
+            inputs.known_dimensions,
+            inputs.parent_size,
+            inputs.available_space,
+            sizing_mode,
+        );
+        if let Some(known_dimensions) = inputs.clamped_known_dimensions(size_and_baselines.size) {
+            size_and_baselines = algorithm.perform_layout(
+                &mut node_ref,
+                known_dimensions,
+                inputs.parent_size,
+                inputs.available_space,
+                sizing_mode,
+            );
+        }
+
+        // Hidden children keep their iced `Tree` state, but are given an empty layout
+        for order in 0..node_ref.children.len() {
+            if node_ref.children[order].is_hidden() {
+                node_ref.perform_child_hidden_layout(order, order as u32);
+            }
+        }
+
+        // Subgrid items were positioned relative to this grid. Make them relative to their
+        // subgrid, whose layout is then built from them.
+        for child in self.children.iter_mut() {
+            if let ChildWidget::Subgrid(subgrid) = &mut child.element {
+                let origin = child.taffy_layout.location;
+                for (order, item) in subgrid.container.children.iter_mut().enumerate() {
+                    if item.is_hidden() {
+                        item.taffy_layout = taffy::Layout {
+                            order: order as u32,
+                            ..taffy::NULL_LAYOUT
+                        };
+                        item.iced_child_layouts.clear();
+                    } else {
+                        item.taffy_layout.location.x -= origin.x;
+                        item.taffy_layout.location.y -= origin.y;
+                    }
+                }
+                child.iced_child_layouts = subgrid.container.child_nodes();
+            }
+        }
+
+        let child_nodes = self.child_nodes();
+        self.store_layout_cache();
+
+        let first_baseline = size_and_baselines.first_baselines.y;
+        let mut node = layout::Node::with_children(
+            Size {
+                width: size_and_baselines.size.width,
+                height: size_and_baselines.size.height,
+            },
+            child_nodes,
+        );
+        if let (true, Some(scale_factor)) = (is_outermost, self.pixel_snapping) {
+            node = snap_node(node, Point::ORIGIN, Point::ORIGIN, scale_factor);
+        }
+        self.laid_out = Some((key, node.clone(), first_baseline));
+        self.cache_pass = Some(key.pass);
+        node
+    }
+
+    /// The first baseline found by the latest layout, if any, which the widget wrapping the
+    /// container reports to its parent
+    pub(crate) fn first_baseline(&self) -> Option<f32> {
+        self.laid_out
+            .as_ref()
+            .and_then(|(_, _, first_baseline)| *first_baseline)
+    }
+
+    /// Build iced layout nodes from the Taffy layouts of the children
+    pub(crate) fn child_nodes(&self) -> Vec<layout::Node> {
+        self.children
+            .iter()
+            .map(|child| {
+                let mut iced_layout = layout::Node::with_children(
+                    Size {
+                        width: child.taffy_layout.size.width,
+                        height: child.taffy_layout.size.height,
+                    },
+                    child.iced_child_layouts.clone(),
+                );
+                iced_layout.move_to(Point {
+                    x: child.taffy_layout.location.x,
+                    y: child.taffy_layout.location.y,
+                });
+                iced_layout
+            })
+            .collect::<Vec<layout::Node>>()
+    }
+
+    pub(crate) fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &R,
+        operation: &mut dyn Operation<Msg>,
+    ) {
+        operation.container(None, &mut |operation| {
+            self.children
+                .iter()
+                .zip(&mut tree.children)
+                .zip(layout.children())
+                .for_each(|((child, state), layout)| {
+                    child
+                        .element
+                        .as_widget()
+                        .operate(state, layout, renderer, operation);
+                })
+        });
+    }
+
+    /// The indices of the visible children in the order that they are drawn: by z-index, and then
+    /// in the order that they were added. A container laid out with [`Algorithm::None`] is hidden
+    /// along with all of its children.
+    fn paint_order(&self, algorithm: Algorithm, tree: &Tree) -> Vec<usize> {
+        if algorithm == Algorithm::None {
+            return Vec::new();
+        }
+        let mut order: Vec<usize> = (0..self.children.len())
+            .filter(|&index| !self.children[index].is_hidden())
+            .collect();
+        order.sort_by_key(|&index| z_index(&tree.children[index]));
+        order
+    }
+
+    /// Dispatch an event to the children, in reverse paint order (so that children drawn on top
+    /// come first), until one of them captures it.
+    ///
+    /// Cursor movements and scrolling are only sent to the children under the cursor, along with
+    /// the child that captured the latest press (so that drags continue outside of it) and the
+    /// child which the cursor was over before (so that it sees the cursor leave). Every other
+    /// event, including presses and releases, is sent to every child until one captures it.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn on_event(
+        &mut self,
+        algorithm: Algorithm,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &R,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Msg>,
+    ) -> event::Status {
+        let state = tree.state.downcast_mut::<ContainerState>();
+        let pointer_owner = state.pointer_owner;
+        let previously_hovered = state.hovered;
+        let hit_tested = is_hit_tested_event(&event);
+
+        let child_layouts: Vec<Layout<'_>> = layout.children().collect();
+        let mut hovered = None;
+        let mut status = event::Status::Ignored;
+        for index in self.paint_order(algorithm, tree).into_iter().rev() {
+            let layout = child_layouts[index];
+            let is_under_cursor = layout.bounds().contains(cursor_position);
+            if is_under_cursor && hovered.is_none() {
+                hovered = Some(index);
+            }
+            if hit_tested
+                && !is_under_cursor
+                && pointer_owner != Some(index)
+                && previously_hovered != Some(index)
+            {
+                continue;
+            }
+
+            status = self.children[index].element.as_widget_mut().on_event(
+                &mut tree.children[index],
+                event.clone(),
+                layout,
+                cursor_position,
+                renderer,
+                clipboard,
+                shell,
+            );
+            if status == event::Status::Captured {
+                if is_press_event(&event) {
+                    tree.state.downcast_mut::<ContainerState>().pointer_owner = Some(index);
+                }
+                break;
+            }
+        }
+
+        let state = tree.state.downcast_mut::<ContainerState>();
+        if hit_tested {
+            state.hovered = hovered;
+        }
+        if is_release_event(&event) {
+            state.pointer_owner = None;
+        }
+        status
+    }
+
+    /// The mouse cursor of the topmost child within the viewport which sets one
+    pub(crate) fn mouse_interaction(
+        &self,
+        algorithm: Algorithm,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+        renderer: &R,
+    ) -> mouse::Interaction {
+        let child_layouts: Vec<Layout<'_>> = layout.children().collect();
+        self.paint_order(algorithm, tree)
+            .into_iter()
+            .rev()
+            .filter(|&index| is_in_viewport(child_layouts[index].bounds(), viewport))
+            .map(|index| {
+                self.children[index].element.as_widget().mouse_interaction(
+                    &tree.children[index],
+                    child_layouts[index],
+                    cursor_position,
+                    viewport,
+                    renderer,
+                )
+            })
+            .find(|&interaction| interaction != mouse::Interaction::default())
+            .unwrap_or_default()
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn draw(
+        &mut self,
+        algorithm: Algorithm,
+        tree: &Tree,
+        renderer: &mut R,
+        theme: &R::Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+    ) {
+        let child_layouts: Vec<Layout<'_>> = layout.children().collect();
+        for index in self.paint_order(algorithm, tree) {
+            // Children outside of the viewport (such as the rows of a large grid which have been
+            // scrolled away) cannot be seen, so are not drawn
+            if !is_in_viewport(child_layouts[index].bounds(), viewport) {
+                continue;
+            }
+            self.children[index].element.as_widget_mut().draw(
+                &tree.children[index],
+                renderer,
+                theme,
+                style,
+                child_layouts[index],
+                cursor_position,
+                viewport,
+            );
+        }
+    }
+
+    pub(crate) fn overlay<'b>(
+        &'b mut self,
+        algorithm: Algorithm,
+        tree: &'b mut Tree,
+        layout: Layout<'_>,
+        renderer: &R,
+    ) -> Option<overlay::Element<'b, Msg, R>> {
+        if algorithm == Algorithm::None {
+            return None;
+        }
+        // Several children may have an overlay open at once (such as a tooltip and a menu), so
+        // all of them are shown, in paint order
+        let z_indices: Vec<i32> = tree.children.iter().map(z_index).collect();
+        let mut overlays: Vec<(usize, overlay::Element<'b, Msg, R>)> = self
+            .children
+            .iter_mut()
+            .zip(&mut tree.children)
+            .zip(layout.children())
+            .enumerate()
+            .filter(|(_, ((child, _), _))| !child.is_hidden())
+            .filter_map(|(index, ((child, state), layout))| {
+                child
+                    .element
+                    .as_widget_mut()
+                    .overlay(state, layout, renderer)
+                    .map(|overlay| (index, overlay))
+            })
+            .collect();
+        overlays.sort_by_key(|&(index, _)| z_indices[index]);
+        OverlayGroup::combine(overlays.into_iter().map(|(_, overlay)| overlay).collect())
+    }
+}
+
+/// Implement the builder methods shared by every Taffy-based widget, for a widget type with a
+/// `container: Container` field
+macro_rules! container_builders {
+    ($widget:ident) => {
+        impl<'a, Msg, R: ::iced_native::renderer::Renderer> $widget<'a, Msg, R> {
+            pub fn min_height(mut self, size: $crate::tree::taffy::Dimension) -> Self {
+                self.container.style.min_size.height = size;
+                self
+            }
+
+            pub fn min_width(mut self, size: $crate::tree::taffy::Dimension) -> Self {
+                self.container.style.min_size.width = size;
+                self
+            }
+
+            pub fn max_height(mut self, size: $crate::tree::taffy::Dimension) -> Self {
+                self.container.style.max_size.height = size;
+                self
+            }
+
+            pub fn max_width(mut self, size: $crate::tree::taffy::Dimension) -> Self {
+                self.container.style.max_size.width = size;
+                self
+            }
+
+            pub fn with_styled_child(
+                mut self,
+                element: impl Into<::iced_native::Element<'a, Msg, R>>,
+                mut callback: impl FnMut(&mut $crate::tree::taffy::Style),
+            ) -> Self {
+                let mut style = $crate::tree::taffy::Style::DEFAULT;
+                callback(&mut style);
+                self.container.push(element.into(), style);
+                self
+            }
+
+            pub fn with_child(
+                mut self,
+                element: impl Into<::iced_native::Element<'a, Msg, R>>,
+            ) -> Self {
+                self.container
+                    .push(element.into(), $crate::tree::taffy::Style::DEFAULT);
+                self
+            }
+
+            pub fn add_child(&mut self, element: impl Into<::iced_native::Element<'a, Msg, R>>) {
+                self.container
+                    .push(element.into(), $crate::tree::taffy::Style::DEFAULT);
+            }
+
+            #[doc = concat!("Sets the width of the [`", stringify!($widget), "`].")]
+            /// This is shorthand for setting `style.size.width`.
+            pub fn width(mut self, width: ::iced_native::Length) -> Self {
+                self.container.set_width(width);
+                self
+            }
+
+            #[doc = concat!("Sets the height of the [`", stringify!($widget), "`].")]
+            /// This is shorthand for setting `style.size.height`.
+            pub fn height(mut self, height: ::iced_native::Length) -> Self {
+                self.container.set_height(height);
+                self
+            }
+
+            /// Sets the scale factor whose physical pixels the edges of the children are snapped
+            /// to, or disables snapping if `None`. Defaults to `Some(1.0)`.
+            ///
+            /// This only has an effect on an outermost container, which snaps the layouts of every
+            /// container nested in it. The setting of a nested container is ignored.
+            ///
+            /// See [`Container::pixel_snapping`]($crate::container::Container::pixel_snapping).
+            pub fn pixel_snapping(mut self, scale_factor: Option<f32>) -> Self {
+                self.container.pixel_snapping = scale_factor;
+                self
+            }
+        }
+    };
+}
+
+/// Implement [`Widget`](iced_native::Widget) for a Taffy-based widget type by forwarding to its
+/// `container: Container` field, laying it out with the [`Algorithm`] returned by its
+/// `algorithm` method
+macro_rules! container_widget {
+    ($widget:ident) => {
+        impl<'a, Msg, R: ::iced_native::renderer::Renderer> ::iced_native::Widget<Msg, R>
+            for $widget<'a, Msg, R>
+        {
+            fn tag(&self) -> ::iced_native::widget::tree::Tag {
+                self.container.tag()
+            }
+
+            fn state(&self) -> ::iced_native::widget::tree::State {
+                self.container.state()
+            }
+
+            fn children(&self) -> Vec<::iced_native::widget::Tree> {
+                self.container.children()
+            }
+
+            fn diff(&self, tree: &mut ::iced_native::widget::Tree) {
+                self.container.diff(tree)
+            }
+
+            fn width(&self) -> ::iced_native::Length {
+                self.container.width()
+            }
+
+            fn height(&self) -> ::iced_native::Length {
+                self.container.height()
+            }
+
+            fn measure(
+                &mut self,
+                renderer: &R,
+                limits: &::iced_native::layout::Limits,
+            ) -> ::iced_native::Size {
+                self.container.measure(self.algorithm(), renderer, limits)
+            }
+
+            fn layout(
+                &mut self,
+                renderer: &R,
+                limits: &::iced_native::layout::Limits,
+            ) -> ::iced_native::layout::Node {
+                let node = self.container.layout(self.algorithm(), renderer, limits);
+                let first_baseline = self.container.first_baseline();
+                $crate::baseline::report_first_baseline(&*self, first_baseline);
+                node
+            }
+
+            fn operate(
+                &self,
+                tree: &mut ::iced_native::widget::Tree,
+                layout: ::iced_native::Layout<'_>,
+                renderer: &R,
+                operation: &mut dyn ::iced_native::widget::Operation<Msg>,
+            ) {
+                self.container.operate(tree, layout, renderer, operation)
+            }
+
+            fn on_event(
+                &mut self,
+                tree: &mut ::iced_native::widget::Tree,
+                event: ::iced_native::event::Event,
+                layout: ::iced_native::Layout<'_>,
+                cursor_position: ::iced_native::Point,
+                renderer: &R,
+                clipboard: &mut dyn ::iced_native::Clipboard,
+                shell: &mut ::iced_native::Shell<'_, Msg>,
+            ) -> ::iced_native::event::Status {
+                let algorithm = self.algorithm();
+                self.container.on_event(
+                    algorithm,
+                    tree,
+                    event,
+                    layout,
+                    cursor_position,
+                    renderer,
+                    clipboard,
+                    shell,
+                )
+            }
+
+            fn mouse_interaction(
+                &self,
+                tree: &::iced_native::widget::Tree,
+                layout: ::iced_native::Layout<'_>,
+                cursor_position: ::iced_native::Point,
+                viewport: &::iced_native::Rectangle,
+                renderer: &R,
+            ) -> ::iced_native::mouse::Interaction {
+                self.container.mouse_interaction(
+                    self.algorithm(),
+                    tree,
+                    layout,
+                    cursor_position,
+                    viewport,
+                    renderer,
+                )
+            }
+
+            fn draw(
+                &mut self,
+                tree: &::iced_native::widget::Tree,
+                renderer: &mut R,
+                theme: &R::Theme,
+                style: &::iced_native::renderer::Style,
+                layout: ::iced_native::Layout<'_>,
+                cursor_position: ::iced_native::Point,
+                viewport: &::iced_native::Rectangle,
+            ) {
+                let algorithm = self.algorithm();
+                self.container.draw(
+                    algorithm,
+                    tree,
+                    renderer,
+                    theme,
+                    style,
+                    layout,
+                    cursor_position,
+                    viewport,
+                )
+            }
+
+            fn overlay<'b>(
+                &'b mut self,
+                tree: &'b mut ::iced_native::widget::Tree,
+                layout: ::iced_native::Layout<'_>,
+                renderer: &R,
+            ) -> Option<::iced_native::overlay::Element<'b, Msg, R>> {
+                let algorithm = self.algorithm();
+                self.container.overlay(algorithm, tree, layout, renderer)
+            }
+        }
+
+        impl<'a, Msg: 'a, R: ::iced_native::renderer::Renderer + 'a> From<$widget<'a, Msg, R>>
+            for ::iced_native::Element<'a, Msg, R>
+        {
+            fn from(widget: $widget<'a, Msg, R>) -> Self {
+                Self::new(widget)
+            }
+        }
+    };
+}
+
+pub(crate) use container_builders;
+pub(crate) use container_widget;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentages_are_reported_as_fill() {
+        for fraction in [0.5, 0.99, 1.0] {
+            assert_eq!(
+                reported_length(Length::Shrink, taffy::Dimension::Percent(fraction)),
+                Length::Fill
+            );
+        }
+    }
+
+    #[test]
+    fn explicitly_set_lengths_round_trip() {
+        for length in [
+            Length::Fill,
+            Length::FillPortion(3),
+            Length::Shrink,
+            Length::Fixed(120.0),
+        ] {
+            assert_eq!(reported_length(length, length_to_dimension(length)), length);
+        }
+    }
+
+    #[test]
+    fn styles_changed_since_the_length_was_set_are_reported() {
+        assert_eq!(
+            reported_length(Length::FillPortion(3), taffy::Dimension::Points(40.0)),
+            Length::Fixed(40.0)
+        );
+        assert_eq!(
+            reported_length(Length::Fixed(40.0), taffy::Dimension::Auto),
+            Length::Shrink
+        );
+    }
+}
@@ -0,0 +1,71 @@
+//! A CSS Flexbox widget based on Taffy
+
+use iced_native::renderer::Renderer;
+
+use crate::container::{container_builders, container_widget, Algorithm, Container};
+use crate::tree::taffy;
+
+pub struct Flex<'a, Msg, R: Renderer> {
+    container: Container<'a, Msg, R>,
+}
+
+impl<'a, Msg, R: Renderer> Flex<'a, Msg, R> {
+    pub fn new() -> Self {
+        Self {
+            container: Container::new(taffy::Style::DEFAULT),
+        }
+    }
+
+    /// Sets the main axis along which children are placed
+    pub fn direction(mut self, direction: taffy::FlexDirection) -> Self {
+        self.container.style.flex_direction = direction;
+        self
+    }
+
+    /// Sets whether children that overflow the main axis wrap onto new lines
+    pub fn wrap(mut self, wrap: taffy::FlexWrap) -> Self {
+        self.container.style.flex_wrap = wrap;
+        self
+    }
+
+    pub fn justify_content(mut self, justify_content: taffy::JustifyContent) -> Self {
+        self.container.style.justify_content = Some(justify_content);
+        self
+    }
+
+    pub fn align_items(mut self, align_items: taffy::AlignItems) -> Self {
+        self.container.style.align_items = Some(align_items);
+        self
+    }
+
+    pub fn align_content(mut self, align_content: taffy::AlignContent) -> Self {
+        self.container.style.align_content = Some(align_content);
+        self
+    }
+
+    pub fn with_column_gap(mut self, gap: taffy::LengthPercentage) -> Self {
+        self.container.style.gap.width = gap;
+        self
+    }
+
+    pub fn with_row_gap(mut self, gap: taffy::LengthPercentage) -> Self {
+        self.container.style.gap.height = gap;
+        self
+    }
+
+    pub fn style(mut self, mut callback: impl FnMut(&mut taffy::Style)) -> Self {
+        callback(&mut self.container.style);
+        self
+    }
+
+    fn algorithm(&self) -> Algorithm {
+        Algorithm::Flex
+    }
+}
+
+container_builders!(Flex);
+container_widget!(Flex);
+
+pub fn flex<'a, Msg, R: Renderer>() -> Flex<'a, Msg, R> {
+    Flex::new()
+}
@@ -1,588 +1,400 @@
 //! A CSS Grid widget based on Taffy
 
-use iced_native::event::{self, Event};
-use iced_native::layout::Limits;
 use iced_native::renderer::Renderer;
-use iced_native::widget::{Operation, Tree};
-use iced_native::{layout, mouse, overlay, renderer};
-use iced_native::{Clipboard, Element, Layout, Length, Point, Rectangle, Shell, Size, Widget};
-
-use ::taffy::LayoutAlgorithm;
-mod taffy {
-    pub use ::taffy::layout::{Layout, RunMode, SizeAndBaselines, SizingMode};
-    pub use ::taffy::*;
-    pub use taffy::cache::Cache;
-    pub use taffy::geometry::*;
-    pub use taffy::prelude::*;
-
-    pub const NULL_LAYOUT: Layout = Layout {
-        order: 0,
-        size: Size::ZERO,
-        location: Point::ZERO,
+use iced_native::Element;
+
+use crate::container::{
+    apply_child_lengths, container_builders, container_widget, Algorithm, Container,
+};
+use crate::grid_areas::{GridTemplateAreas, GridTemplateAreasError};
+use crate::grid_lines::{GridLineNames, GridTrackList};
+use crate::tree::{taffy, ChildWidget, TaffyChild};
+
+/// The lines of the parent grid spanned by a subgrid with the given placement, if they are
+/// definite
+fn definite_lines(placement: taffy::Line<taffy::GridPlacement>) -> Option<(i16, i16)> {
+    type Placement = taffy::GridPlacement;
+    let (start, end) = match (placement.start, placement.end) {
+        (Placement::Line(start), Placement::Line(end)) => (start.as_i16(), end.as_i16()),
+        (Placement::Line(start), Placement::Span(span)) => {
+            (start.as_i16(), start.as_i16() + span as i16)
+        }
+        (Placement::Line(start), Placement::Auto) => (start.as_i16(), start.as_i16() + 1),
+        (Placement::Span(span), Placement::Line(end)) => (end.as_i16() - span as i16, end.as_i16()),
+        (Placement::Auto, Placement::Line(end)) => (end.as_i16() - 1, end.as_i16()),
+        _ => return None,
     };
-}
-
-fn f32_to_opt(input: f32) -> Option<f32> {
-    if input.is_nan() || input.is_infinite() {
-        None
+    if start > 0 && end > 0 && start != end {
+        Some((start.min(end), start.max(end)))
     } else {
-        Some(input)
+        None
     }
 }
 
-struct GridLayoutTree<'node, 'a, 'b, Msg, R: Renderer> {
-    grid: &'node mut Grid<'a, Msg, R>,
-    renderer: &'b R,
-    layout: taffy::Layout,
+/// Translate the placement of a subgrid item into the lines of the parent grid, where the subgrid
+/// spans `lines`. Returns `None` if the item is auto-placed.
+fn translate_placement(
+    placement: taffy::Line<taffy::GridPlacement>,
+    lines: (i16, i16),
+) -> Option<taffy::Line<taffy::GridPlacement>> {
+    type Placement = taffy::GridPlacement;
+    let is_line = |placement| matches!(placement, Placement::Line(_));
+    if !is_line(placement.start) && !is_line(placement.end) {
+        return None;
+    }
+    let translate = |placement| match placement {
+        // Negative lines count backwards from the end of the subgrid
+        Placement::Line(line) if line.as_i16() < 0 => {
+            taffy::style_helpers::line(lines.1 + line.as_i16() + 1)
+        }
+        Placement::Line(line) => taffy::style_helpers::line(lines.0 + line.as_i16() - 1),
+        other => other,
+    };
+    Some(taffy::Line {
+        start: translate(placement.start),
+        end: translate(placement.end),
+    })
 }
 
-const CURRENT_NODE_ID : taffy::NodeId = taffy::NodeId::new(u64::MAX);
-
-/// Iterator that wraps a range of u64, lazily converting them to NodeId's
-pub struct GridChildIter(std::ops::Range<usize>);
-impl Iterator for GridChildIter {
-    type Item = taffy::NodeId;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        self.0.next().map(|idx| idx.into())
+/// The number of tracks spanned by an auto-placed item
+fn placement_span(placement: taffy::Line<taffy::GridPlacement>) -> i16 {
+    match (placement.start, placement.end) {
+        (taffy::GridPlacement::Span(span), _) | (_, taffy::GridPlacement::Span(span)) => {
+            span as i16
+        }
+        _ => 1,
     }
 }
 
-impl<'node, 'a, 'b, Msg, R: Renderer> taffy::LayoutTree for GridLayoutTree<'node, 'a, 'b, Msg, R> {
-    type ChildIter<'iter> = GridChildIter where Self: 'iter;
+pub struct Grid<'a, Msg, R: Renderer> {
+    pub(crate) container: Container<'a, Msg, R>,
+    areas: GridTemplateAreas,
+    line_names: GridLineNames,
+    /// Whether this grid uses its parent's columns (width) and rows (height) as a subgrid
+    subgrid: taffy::Size<bool>,
+    /// The styles of this grid's children, with their placements translated into the lines of the
+    /// parent grid. Only set when this grid is a subgrid.
+    pub(crate) flattened_styles: Vec<taffy::Style>,
+    masonry: bool,
+}
 
-    fn style(&self, node: taffy::NodeId) -> &taffy::Style {
-        if node == CURRENT_NODE_ID {
-            &self.grid.style
-        } else {
-            let child_index : usize = node.into();
-            &self.grid.children[child_index].style
+impl<'a, Msg, R: Renderer> Grid<'a, Msg, R> {
+    pub fn new() -> Self {
+        Self {
+            container: Container::new(taffy::Style {
+                display: taffy::Display::Grid,
+                ..taffy::Style::DEFAULT
+            }),
+            areas: GridTemplateAreas::default(),
+            line_names: GridLineNames::default(),
+            subgrid: taffy::Size {
+                width: false,
+                height: false,
+            },
+            flattened_styles: Vec::new(),
+            masonry: false,
         }
     }
 
-    fn layout_mut(&mut self, node: taffy::NodeId) -> &mut taffy::Layout {
-        if node == CURRENT_NODE_ID {
-            panic!();
+    /// Lays out the children as a masonry grid: the columns are sized as usual, but instead of
+    /// being placed in rows each child is packed into the column where it can sit highest, directly
+    /// below the children already in that column.
+    ///
+    /// Children are placed in order, and may span several columns or be pinned to a column with
+    /// `grid_column`. Row templates and placements are ignored, and children added with
+    /// [`with_subgrid`](Self::with_subgrid) (before or after this is called) are laid out as
+    /// ordinary nested grids.
+    pub fn masonry(mut self) -> Self
+    where
+        Msg: 'a,
+        R: 'a,
+    {
+        self.masonry = true;
+        // Subgrids which were already flattened into this grid are made ordinary children again
+        self.container.children = std::mem::take(&mut self.container.children)
+            .into_iter()
+            .map(|child| match child.element {
+                ChildWidget::Subgrid(subgrid) => TaffyChild::new(
+                    ChildWidget::Element((*subgrid).into()),
+                    child.explicit_style,
+                ),
+                element => TaffyChild { element, ..child },
+            })
+            .collect();
+        self
+    }
+
+    fn algorithm(&self) -> Algorithm {
+        if self.masonry {
+            Algorithm::Masonry
         } else {
-            let child_index : usize = node.into();
-            &mut self.grid.children[child_index].taffy_layout
+            Algorithm::Grid
         }
     }
 
-    fn children(&self, node: taffy::NodeId) -> Self::ChildIter<'_> {
-        GridChildIter(0..(self.child_count(node)))
+    /// Makes this grid a subgrid in both axes when it is added to a parent grid with
+    /// [`with_subgrid`](Self::with_subgrid). See [`subgrid_columns`](Self::subgrid_columns).
+    pub fn subgrid(self) -> Self {
+        self.subgrid_columns().subgrid_rows()
     }
 
-    fn child_count(&self, node: taffy::NodeId) -> usize {
-        self.grid.children.len()
+    /// Makes this grid use the column tracks of its parent grid instead of its own, so that its
+    /// children line up with the parent's other children. The children are sized together with
+    /// the parent grid's children, and contribute to the size of its tracks.
+    ///
+    /// In an axis which is not subgridded, every child spans all of the parent tracks covered by
+    /// the subgrid: the subgrid's own tracks in that axis are not used.
+    pub fn subgrid_columns(mut self) -> Self {
+        self.subgrid.width = true;
+        self
     }
 
-    fn child(&self, node: taffy::NodeId, index: usize) -> taffy::NodeId {
-        index.into()
+    /// Makes this grid use the row tracks of its parent grid instead of its own. See
+    /// [`subgrid_columns`](Self::subgrid_columns).
+    pub fn subgrid_rows(mut self) -> Self {
+        self.subgrid.height = true;
+        self
     }
 
-    fn measure_child_size(
-        &mut self,
-        child_node_id: taffy::NodeId,
-        known_dimensions: taffy::Size<Option<f32>>,
-        _parent_size: taffy::Size<Option<f32>>,
-        available_space: taffy::Size<taffy::AvailableSpace>,
-        _sizing_mode: taffy::SizingMode,
-    ) -> taffy::Size<f32> {
-        let mut limits = Limits::NONE;
-
-        // Set constraints based on available_space
-        if let taffy::AvailableSpace::Definite(height) = available_space.height {
-            limits = limits.max_height(height.round());
-        }
-        if let taffy::AvailableSpace::Definite(width) = available_space.width {
-            limits = limits.max_width(width.round());
-        }
-
-        // Set constraints based on known dimensions
-        if let Some(height) = known_dimensions.height {
-            limits = limits.height(Length::Fixed(height.round()))
-        }
-        if let Some(width) = known_dimensions.width {
-            limits = limits.width(Length::Fixed(width.round()))
-        }
-
-        let child_index : usize = child_node_id.into();
-        let child = &mut self.grid.children[child_index];
-        let cached_size = child.cache.get(
-            known_dimensions,
-            available_space,
-            taffy::RunMode::ComputeSize,
-        );
-
-        let size = cached_size
-            .map(|size_and_baselines| size_and_baselines.size)
-            .unwrap_or_else(|| {
-                // Compute child layout
-                let iced_size = child
-                    .element
-                    .as_widget_mut()
-                    .measure(&self.renderer, &limits);
-                let taffy_size = taffy::Size {
-                    width: iced_size.width,
-                    height: iced_size.height,
-                };
-                child.cache.store(
-                    known_dimensions,
-                    available_space,
-                    taffy::RunMode::ComputeSize,
-                    taffy_size.into(),
-                );
-                taffy_size
-            });
-
-        // Return size
-        size
+    /// Whether any of this grid's children were added as subgrids
+    fn has_subgrids(&self) -> bool {
+        self.container
+            .children
+            .iter()
+            .any(|child| matches!(child.element, ChildWidget::Subgrid(_)))
     }
 
-    fn perform_child_layout(
-        &mut self,
-        child_node_id: taffy::NodeId,
-        known_dimensions: taffy::Size<Option<f32>>,
-        _parent_size: taffy::Size<Option<f32>>,
-        available_space: taffy::Size<taffy::AvailableSpace>,
-        _sizing_mode: taffy::SizingMode,
-    ) -> taffy::SizeAndBaselines {
-        let mut limits = Limits::NONE;
-
-        // Set constraints based on available_space
-        if let taffy::AvailableSpace::Definite(height) = available_space.height {
-            limits = limits.max_height(height.round());
-        }
-        if let taffy::AvailableSpace::Definite(width) = available_space.width {
-            limits = limits.max_width(width.round());
+    /// Translate the placements of this subgrid's children into the lines of the parent grid, given
+    /// the placement of this grid within the parent. Returns `false` if this grid is not a subgrid,
+    /// or is not placed at definite lines in its subgridded axes.
+    fn flatten_into_parent(&mut self, parent_placement: &taffy::Style) -> bool {
+        if !self.subgrid.width && !self.subgrid.height {
+            return false;
         }
+        let (Some(columns), Some(rows)) = (
+            definite_lines(parent_placement.grid_column),
+            definite_lines(parent_placement.grid_row),
+        ) else {
+            return false;
+        };
+        let column_count = columns.1 - columns.0;
+        let row_count = rows.1 - rows.0;
 
-        // Set constraints based on known dimensions
-        if let Some(height) = known_dimensions.height {
-            limits = limits.height(Length::Fixed(height.round()))
-        }
-        if let Some(width) = known_dimensions.width {
-            limits = limits.width(Length::Fixed(width.round()))
-        }
+        let spanned = |lines: (i16, i16)| taffy::Line {
+            start: taffy::style_helpers::line(lines.0),
+            end: taffy::style_helpers::line(lines.1),
+        };
 
-        let child_index : usize = child_node_id.into();
-        let child = &mut self.grid.children[child_index];
-        let cached_layout = child.cache.get(
-            known_dimensions,
-            available_space,
-            taffy::RunMode::PeformLayout,
-        );
-
-        let layout = cached_layout.unwrap_or_else(|| {
-            // Compute child layout
-            let iced_layout = child
-                .element
-                .as_widget_mut()
-                .layout(&self.renderer, &limits);
-            let bounds = iced_layout.bounds();
-            let taffy_layout = taffy::SizeAndBaselines {
-                size: taffy::Size {
-                    width: bounds.width,
-                    height: bounds.height,
-                },
-                first_baselines: taffy::Point::NONE,
-            };
-            child.cache.store(
-                known_dimensions,
-                available_space,
-                taffy::RunMode::PeformLayout,
-                taffy_layout,
+        // Auto-placed children fill the subgrid's cells in row-major order
+        let mut cursor = (0, 0);
+        let mut flattened_styles = Vec::with_capacity(self.container.children.len());
+        for child in self.container.children.iter() {
+            let mut style = child.explicit_style.clone();
+            let widget = child.element.as_widget();
+            apply_child_lengths(
+                &mut style,
+                widget.width(),
+                widget.height(),
+                Algorithm::Grid,
+                taffy::FlexDirection::Row,
             );
-            child.iced_child_layouts = iced_layout.into_children();
-            taffy_layout
-        });
-
-        // Return size
-        layout
-    }
-
-    // fn perform_child_hidden_layout(&mut self, child_node_id: taffy::NodeId, order: u32) {
-    //     self.grid.children[child_node_id].taffy_layout = taffy::Layout::with_order(order);
-    // }
-}
-
-struct GridChild<'a, Msg, R: Renderer> {
-    element: Element<'a, Msg, R>,
-    style: taffy::Style,
-    cache: taffy::Cache,
-    taffy_layout: taffy::Layout,
-    iced_child_layouts: Vec<iced_native::layout::Node>,
-}
+            let column = if self.subgrid.width {
+                translate_placement(style.grid_column, columns)
+            } else {
+                Some(spanned(columns))
+            };
+            let row = if self.subgrid.height {
+                translate_placement(style.grid_row, rows)
+            } else {
+                Some(spanned(rows))
+            };
 
-impl<'a, Msg, R: Renderer> GridChild<'a, Msg, R> {
-    fn new(element: Element<'a, Msg, R>, style: taffy::Style) -> Self {
-        Self {
-            element,
-            style,
-            cache: taffy::Cache::new(),
-            taffy_layout: taffy::NULL_LAYOUT,
-            iced_child_layouts: vec![],
+            if let (Some(column), Some(row)) = (column, row) {
+                style.grid_column = column;
+                style.grid_row = row;
+            } else {
+                // Spans are clamped so that every child stays within the subgrid's area
+                let column_span = placement_span(style.grid_column).min(column_count);
+                let row_span = placement_span(style.grid_row).min(row_count);
+                if self.subgrid.width && cursor.1 + column_span > column_count {
+                    // Start the next row, or in a subgrid without rows of its own, start again
+                    // from the first column
+                    cursor.1 = 0;
+                    if self.subgrid.height {
+                        cursor.0 += 1;
+                    }
+                }
+                if self.subgrid.height && cursor.0 + row_span > row_count {
+                    // Once every row is full, children are placed over those already placed
+                    cursor.0 = 0;
+                }
+                style.grid_column = column.unwrap_or_else(|| taffy::Line {
+                    start: taffy::style_helpers::line(columns.0 + cursor.1),
+                    end: taffy::style_helpers::span(column_span as u16),
+                });
+                style.grid_row = row.unwrap_or_else(|| taffy::Line {
+                    start: taffy::style_helpers::line(rows.0 + cursor.0),
+                    end: taffy::style_helpers::span(row_span as u16),
+                });
+                if self.subgrid.width {
+                    cursor.1 += column_span;
+                } else {
+                    cursor.0 += row_span;
+                }
+            }
+            flattened_styles.push(style);
         }
-    }
-}
+        self.flattened_styles = flattened_styles;
 
-pub struct Grid<'a, Msg, R: Renderer> {
-    width: Length,
-    height: Length,
-    style: taffy::Style,
-    children: Vec<GridChild<'a, Msg, R>>,
-}
-
-impl<'a, Msg, R: Renderer> Grid<'a, Msg, R> {
-    pub fn new() -> Self {
-        Self {
-            width: Length::Fill,
-            height: Length::Fill,
-            style: taffy::Style::DEFAULT,
-            children: vec![],
-        }
+        true
     }
 
     pub fn with_columns(mut self, columns: Vec<taffy::TrackSizingFunction>) -> Self {
-        self.style.grid_template_columns = columns;
+        self.container.style.grid_template_columns = columns;
+        self.line_names.columns.clear();
         self
     }
 
     pub fn with_rows(mut self, rows: Vec<taffy::TrackSizingFunction>) -> Self {
-        self.style.grid_template_rows = rows;
+        self.container.style.grid_template_rows = rows;
+        self.line_names.rows.clear();
         self
     }
 
-    pub fn with_column_gap(mut self, gap: taffy::LengthPercentage) -> Self {
-        self.style.gap.width = gap;
+    /// Sets the column tracks of the grid along with names for the lines between them, which can
+    /// be used to place children with [`with_placed_child`](Self::with_placed_child).
+    pub fn with_named_columns(mut self, columns: GridTrackList) -> Self {
+        let (tracks, line_names) = columns.into_parts();
+        self.container.style.grid_template_columns = tracks;
+        self.line_names.columns = line_names;
         self
     }
 
-    pub fn with_row_gap(mut self, gap: taffy::LengthPercentage) -> Self {
-        self.style.gap.height = gap;
+    /// Sets the row tracks of the grid along with names for the lines between them, which can be
+    /// used to place children with [`with_placed_child`](Self::with_placed_child).
+    pub fn with_named_rows(mut self, rows: GridTrackList) -> Self {
+        let (tracks, line_names) = rows.into_parts();
+        self.container.style.grid_template_rows = tracks;
+        self.line_names.rows = line_names;
         self
     }
 
-    pub fn min_height(mut self, size: taffy::Dimension) -> Self {
-        self.style.min_size.height = size;
-        self
+    /// Names areas of the grid which children can be placed into with
+    /// [`with_child_in_area`](Self::with_child_in_area). See [`GridTemplateAreas`] for the syntax.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the template is invalid. Use [`try_with_areas`](Self::try_with_areas) to handle
+    /// the error instead.
+    pub fn with_areas(self, rows: &[&str]) -> Self {
+        self.try_with_areas(rows)
+            .unwrap_or_else(|err| panic!("invalid grid template areas: {}", err))
     }
 
-    pub fn min_width(mut self, size: taffy::Dimension) -> Self {
-        self.style.min_size.width = size;
-        self
+    pub fn try_with_areas(mut self, rows: &[&str]) -> Result<Self, GridTemplateAreasError> {
+        self.areas = GridTemplateAreas::parse(rows)?;
+        Ok(self)
     }
 
-    pub fn max_height(mut self, size: taffy::Dimension) -> Self {
-        self.style.max_size.height = size;
+    pub fn with_column_gap(mut self, gap: taffy::LengthPercentage) -> Self {
+        self.container.style.gap.width = gap;
         self
     }
 
-    pub fn max_width(mut self, size: taffy::Dimension) -> Self {
-        self.style.max_size.width = size;
+    pub fn with_row_gap(mut self, gap: taffy::LengthPercentage) -> Self {
+        self.container.style.gap.height = gap;
         self
     }
 
     pub fn style(mut self, mut callback: impl FnMut(&mut taffy::Style)) -> Self {
-        callback(&mut self.style);
+        callback(&mut self.container.style);
         self
     }
 
-    pub fn with_styled_child(
+    /// Adds a child which covers the named area.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no area with that name was defined by [`with_areas`](Self::with_areas).
+    pub fn with_child_in_area(self, element: impl Into<Element<'a, Msg, R>>, area: &str) -> Self {
+        self.with_styled_child_in_area(element, area, |_| {})
+    }
+
+    /// Adds a child which covers the named area, after applying `callback` to its style.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no area with that name was defined by [`with_areas`](Self::with_areas).
+    pub fn with_styled_child_in_area(
         mut self,
         element: impl Into<Element<'a, Msg, R>>,
+        area: &str,
         mut callback: impl FnMut(&mut taffy::Style),
     ) -> Self {
         let mut style = taffy::Style::DEFAULT;
+        if !self.areas.place(area, &mut style) {
+            panic!("no grid area named `{}`", area);
+        }
         callback(&mut style);
-        self.children.push(GridChild::new(element.into(), style));
-        self
-    }
-
-    pub fn with_child(mut self, element: impl Into<Element<'a, Msg, R>>) -> Self {
-        self.children
-            .push(GridChild::new(element.into(), taffy::Style::DEFAULT));
+        self.container.push(element.into(), style);
         self
     }
 
-    pub fn add_child(&mut self, element: impl Into<Element<'a, Msg, R>>) {
-        self.children
-            .push(GridChild::new(element.into(), taffy::Style::DEFAULT));
-    }
-
-    /// Sets the width of the [`Grid`].
-    pub fn width(mut self, width: Length) -> Self {
-        self.width = width;
+    /// Adds a child whose style is set by `callback`, which can look up the grid's named lines.
+    ///
+    /// ```ignore
+    /// grid.with_placed_child(sidebar, |lines, style| {
+    ///     style.grid_column = lines.columns("sidebar-start", "sidebar-end");
+    /// })
+    /// ```
+    pub fn with_placed_child(
+        mut self,
+        element: impl Into<Element<'a, Msg, R>>,
+        mut callback: impl FnMut(&GridLineNames, &mut taffy::Style),
+    ) -> Self {
+        let mut style = taffy::Style::DEFAULT;
+        callback(&self.line_names, &mut style);
+        self.container.push(element.into(), style);
         self
     }
 
-    /// Sets the height of the [`Grid`].
-    pub fn height(mut self, height: Length) -> Self {
-        self.height = height;
+    /// Adds a child grid, which becomes a subgrid if it was created with
+    /// [`subgrid`](Self::subgrid), [`subgrid_columns`](Self::subgrid_columns) or
+    /// [`subgrid_rows`](Self::subgrid_rows).
+    ///
+    /// A subgrid must be placed at definite lines of this grid by `callback` (for example using
+    /// `line` and `span` from [`style_helpers`](crate::style_helpers)). Otherwise it is laid out
+    /// as an ordinary nested grid. So is a grid which has subgrids of its own: subgrids only
+    /// share the tracks of their direct parent.
+    pub fn with_subgrid(
+        mut self,
+        mut subgrid: Grid<'a, Msg, R>,
+        mut callback: impl FnMut(&mut taffy::Style),
+    ) -> Self
+    where
+        Msg: 'a,
+        R: 'a,
+    {
+        let mut style = taffy::Style::DEFAULT;
+        callback(&mut style);
+        if !self.masonry && !subgrid.has_subgrids() && subgrid.flatten_into_parent(&style) {
+            // The subgrid fills the grid area that it spans
+            style.align_self = Some(taffy::AlignSelf::Stretch);
+            style.justify_self = Some(taffy::AlignSelf::Stretch);
+            self.container.children.push(TaffyChild::new(
+                ChildWidget::Subgrid(Box::new(subgrid)),
+                style,
+            ));
+        } else {
+            self.container.push(subgrid.into(), style);
+        }
         self
     }
 }
 
+container_builders!(Grid);
+container_widget!(Grid);
+
 pub fn grid<'a, Msg, R: Renderer>() -> Grid<'a, Msg, R> {
     Grid::new()
 }
-
-impl<'a, Msg, R: Renderer> Widget<Msg, R> for Grid<'a, Msg, R> {
-    fn children(&self) -> Vec<Tree> {
-        self.children
-            .iter()
-            .map(|child| Tree::new(&child.element))
-            .collect()
-    }
-
-    fn diff(&self, tree: &mut Tree) {
-        tree.diff_children(
-            &self
-                .children
-                .iter()
-                .map(|child| child.element.as_widget())
-                .collect::<Vec<_>>(),
-        );
-    }
-
-    fn width(&self) -> Length {
-        // match self.style.size.width {
-        //     Dimension::Auto => Length::Shrink,
-        //     Dimension::Points(units) => Length::Fixed(units.round() as u16),
-        //     Dimension::Percent(_) => Length::Fill,
-        // }
-        self.width
-    }
-
-    fn height(&self) -> Length {
-        // match self.style.size.height {
-        //     Dimension::Auto => Length::Shrink,
-        //     Dimension::Points(units) => Length::Fixed(units.round() as u16),
-        //     Dimension::Percent(_) => Length::Fill,
-        // }
-        self.height
-    }
-
-    fn measure(&mut self, renderer: &R, limits: &layout::Limits) -> iced_native::Size {
-        let mut node_ref = GridLayoutTree {
-            grid: self,
-            renderer,
-            layout: taffy::NULL_LAYOUT,
-        };
-
-        let mut known_dimensions = taffy::Size::NONE;
-        if limits.min().height < f32::INFINITY && limits.min().height == limits.max().height {
-            known_dimensions.height = Some(limits.min().height);
-        }
-        if limits.min().width < f32::INFINITY && limits.min().width == limits.max().width {
-            known_dimensions.width = Some(limits.min().width);
-        }
-        let parent_size = taffy::Size {
-            width: f32_to_opt(limits.max().width),
-            height: f32_to_opt(limits.max().height),
-        };
-        let available_space = parent_size.map(|s| s.into());
-        let sizing_mode = taffy::SizingMode::InherentSize;
-
-        let size = taffy::CssGridAlgorithm::measure_size(
-            &mut node_ref,
-            CURRENT_NODE_ID,
-            known_dimensions,
-            parent_size,
-            available_space,
-            sizing_mode,
-        );
-
-        Size {
-            width: size.width,
-            height: size.height,
-        }
-    }
-
-    fn layout(&mut self, renderer: &R, limits: &layout::Limits) -> layout::Node {
-        let mut node_ref = GridLayoutTree {
-            grid: self,
-            renderer,
-            layout: taffy::NULL_LAYOUT,
-        };
-
-        let mut known_dimensions = taffy::Size::NONE;
-        if limits.min().height < f32::INFINITY && limits.min().height == limits.max().height {
-            known_dimensions.height = Some(limits.min().height);
-        }
-        if limits.min().width < f32::INFINITY && limits.min().width == limits.max().width {
-            known_dimensions.width = Some(limits.min().width);
-        }
-        let parent_size = taffy::Size {
-            width: f32_to_opt(limits.max().width),
-            height: f32_to_opt(limits.max().height),
-        };
-        let available_space = parent_size.map(|s| s.into());
-        let sizing_mode = taffy::SizingMode::InherentSize;
-
-        let size_and_baselines = taffy::CssGridAlgorithm::perform_layout(
-            &mut node_ref,
-            CURRENT_NODE_ID,
-            known_dimensions,
-            parent_size,
-            available_space,
-            sizing_mode,
-        );
-
-        let child_nodes = self
-            .children
-            .iter_mut()
-            .map(|child| {
-                // child.taffy_layout.round();
-                let mut iced_layout = layout::Node::with_children(
-                    Size {
-                        width: child.taffy_layout.size.width,
-                        height: child.taffy_layout.size.height,
-                    },
-                    child.iced_child_layouts.clone(),
-                );
-                iced_layout.move_to(Point {
-                    x: child.taffy_layout.location.x,
-                    y: child.taffy_layout.location.y,
-                });
-                iced_layout
-            })
-            .collect::<Vec<layout::Node>>();
-
-        return layout::Node::with_children(
-            Size {
-                width: size_and_baselines.size.width,
-                height: size_and_baselines.size.height,
-            },
-            child_nodes,
-        );
-    }
-
-    fn operate(
-        &self,
-        tree: &mut Tree,
-        layout: Layout<'_>,
-        renderer: &R,
-        operation: &mut dyn Operation<Msg>,
-    ) {
-        operation.container(None, &mut |operation| {
-            self.children
-                .iter()
-                .zip(&mut tree.children)
-                .zip(layout.children())
-                .for_each(|((child, state), layout)| {
-                    child
-                        .element
-                        .as_widget()
-                        .operate(state, layout, renderer, operation);
-                })
-        });
-    }
-
-    fn on_event(
-        &mut self,
-        tree: &mut Tree,
-        event: Event,
-        layout: Layout<'_>,
-        cursor_position: Point,
-        renderer: &R,
-        clipboard: &mut dyn Clipboard,
-        shell: &mut Shell<'_, Msg>,
-    ) -> event::Status {
-        self.children
-            .iter_mut()
-            .zip(&mut tree.children)
-            .zip(layout.children())
-            .map(|((child, state), layout)| {
-                child.element.as_widget_mut().on_event(
-                    state,
-                    event.clone(),
-                    layout,
-                    cursor_position,
-                    renderer,
-                    clipboard,
-                    shell,
-                )
-            })
-            .fold(event::Status::Ignored, event::Status::merge)
-    }
-
-    fn mouse_interaction(
-        &self,
-        tree: &Tree,
-        layout: Layout<'_>,
-        cursor_position: Point,
-        viewport: &Rectangle,
-        renderer: &R,
-    ) -> mouse::Interaction {
-        self.children
-            .iter()
-            .zip(&tree.children)
-            .zip(layout.children())
-            .map(|((child, state), layout)| {
-                child.element.as_widget().mouse_interaction(
-                    state,
-                    layout,
-                    cursor_position,
-                    viewport,
-                    renderer,
-                )
-            })
-            .max()
-            .unwrap_or_default()
-    }
-
-    fn draw(
-        &mut self,
-        tree: &Tree,
-        renderer: &mut R,
-        theme: &R::Theme,
-        style: &renderer::Style,
-        layout: Layout<'_>,
-        cursor_position: Point,
-        viewport: &Rectangle,
-    ) {
-        for ((child, state), layout) in self
-            .children
-            .iter_mut()
-            .zip(&tree.children)
-            .zip(layout.children())
-        {
-            child.element.as_widget_mut().draw(
-                state,
-                renderer,
-                theme,
-                style,
-                layout,
-                cursor_position,
-                viewport,
-            );
-        }
-    }
-
-    fn overlay<'b>(
-        &'b mut self,
-        tree: &'b mut Tree,
-        layout: Layout<'_>,
-        renderer: &R,
-    ) -> Option<overlay::Element<'b, Msg, R>> {
-        // This calls the first overlay. We probably want all overlays?
-        self.children
-            .iter_mut()
-            .zip(&mut tree.children)
-            .zip(layout.children())
-            .find_map(|((child, state), layout)| {
-                child
-                    .element
-                    .as_widget_mut()
-                    .overlay(state, layout, renderer)
-            })
-    }
-}
-
-impl<'a, Msg: 'a, R: Renderer + 'a> From<Grid<'a, Msg, R>> for Element<'a, Msg, R> {
-    fn from(grid: Grid<'a, Msg, R>) -> Self {
-        Self::new(grid)
-    }
-}
@@ -0,0 +1,220 @@
+//! Parsing of named grid template areas (the CSS `grid-template-areas` property)
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::tree::taffy;
+
+/// An error encountered while parsing grid template areas
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GridTemplateAreasError {
+    /// The template contained no rows, or a row contained no cells
+    Empty,
+    /// A row contained a different number of cells to the first row
+    MismatchedRowLength {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// The cells with the given name do not form a single filled-in rectangle
+    NonRectangular(String),
+}
+
+impl fmt::Display for GridTemplateAreasError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GridTemplateAreasError::Empty => {
+                write!(f, "grid template areas must contain at least one cell")
+            }
+            GridTemplateAreasError::MismatchedRowLength {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "row {} of the grid template areas has {} cells, but row 1 has {}",
+                row, found, expected
+            ),
+            GridTemplateAreasError::NonRectangular(name) => {
+                write!(f, "grid area `{}` is not a rectangle", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GridTemplateAreasError {}
+
+/// The grid lines bounding a named area. Lines are 1-based, as in CSS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct GridArea {
+    row_start: i16,
+    row_end: i16,
+    column_start: i16,
+    column_end: i16,
+}
+
+/// A set of named areas parsed from a template such as `["header header", "nav main"]`.
+///
+/// Each string is one row of the grid, and contains one whitespace-separated name per column.
+/// A `.` marks a cell which does not belong to any area. The cells sharing a name must form a
+/// rectangle.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GridTemplateAreas {
+    areas: HashMap<String, GridArea>,
+}
+
+impl GridTemplateAreas {
+    pub fn parse(rows: &[&str]) -> Result<Self, GridTemplateAreasError> {
+        let cells: Vec<Vec<&str>> = rows
+            .iter()
+            .map(|row| row.split_whitespace().collect())
+            .collect();
+        let column_count = cells.first().map(|row| row.len()).unwrap_or(0);
+        if column_count == 0 {
+            return Err(GridTemplateAreasError::Empty);
+        }
+
+        let mut areas: HashMap<String, GridArea> = HashMap::new();
+        let mut cell_counts: HashMap<&str, usize> = HashMap::new();
+        for (row_index, row) in cells.iter().enumerate() {
+            if row.len() != column_count {
+                return Err(GridTemplateAreasError::MismatchedRowLength {
+                    row: row_index + 1,
+                    expected: column_count,
+                    found: row.len(),
+                });
+            }
+            for (column_index, &name) in row.iter().enumerate() {
+                if name.chars().all(|c| c == '.') {
+                    continue;
+                }
+                let (row_line, column_line) = (row_index as i16 + 1, column_index as i16 + 1);
+                let area = areas.entry(name.to_string()).or_insert(GridArea {
+                    row_start: row_line,
+                    row_end: row_line + 1,
+                    column_start: column_line,
+                    column_end: column_line + 1,
+                });
+                area.row_start = area.row_start.min(row_line);
+                area.row_end = area.row_end.max(row_line + 1);
+                area.column_start = area.column_start.min(column_line);
+                area.column_end = area.column_end.max(column_line + 1);
+                *cell_counts.entry(name).or_insert(0) += 1;
+            }
+        }
+
+        // An area is rectangular if its cells exactly fill its bounding box
+        for (name, area) in areas.iter() {
+            let bounding_box_cells = (area.row_end - area.row_start) as usize
+                * (area.column_end - area.column_start) as usize;
+            if cell_counts[name.as_str()] != bounding_box_cells {
+                return Err(GridTemplateAreasError::NonRectangular(name.clone()));
+            }
+        }
+
+        Ok(Self { areas })
+    }
+
+    /// Set the row and column placement of `style` to cover the named area.
+    ///
+    /// Returns `false` if there is no area with that name.
+    pub(crate) fn place(&self, name: &str, style: &mut taffy::Style) -> bool {
+        let Some(area) = self.areas.get(name) else {
+            return false;
+        };
+        style.grid_row = taffy::Line {
+            start: taffy::style_helpers::line(area.row_start),
+            end: taffy::style_helpers::line(area.row_end),
+        };
+        style.grid_column = taffy::Line {
+            start: taffy::style_helpers::line(area.column_start),
+            end: taffy::style_helpers::line(area.column_end),
+        };
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::line;
+
+    fn area(row_start: i16, row_end: i16, column_start: i16, column_end: i16) -> GridArea {
+        GridArea {
+            row_start,
+            row_end,
+            column_start,
+            column_end,
+        }
+    }
+
+    #[test]
+    fn areas_cover_the_lines_around_their_cells() {
+        let areas = GridTemplateAreas::parse(&[
+            "header header header",
+            "nav    main   main",
+            "nav    main   main",
+        ])
+        .unwrap();
+        assert_eq!(areas.areas.len(), 3);
+        assert_eq!(areas.areas["header"], area(1, 2, 1, 4));
+        assert_eq!(areas.areas["nav"], area(2, 4, 1, 2));
+        assert_eq!(areas.areas["main"], area(2, 4, 2, 4));
+    }
+
+    #[test]
+    fn dot_cells_belong_to_no_area() {
+        let areas = GridTemplateAreas::parse(&[". top ...", "left . ."]).unwrap();
+        assert_eq!(areas.areas.len(), 2);
+        assert_eq!(areas.areas["top"], area(1, 2, 2, 3));
+        assert_eq!(areas.areas["left"], area(2, 3, 1, 2));
+    }
+
+    #[test]
+    fn templates_without_cells_are_empty() {
+        assert_eq!(
+            GridTemplateAreas::parse(&[]),
+            Err(GridTemplateAreasError::Empty)
+        );
+        assert_eq!(
+            GridTemplateAreas::parse(&["   "]),
+            Err(GridTemplateAreasError::Empty)
+        );
+    }
+
+    #[test]
+    fn rows_must_have_as_many_cells_as_the_first() {
+        assert_eq!(
+            GridTemplateAreas::parse(&["a b", "c d", "e"]),
+            Err(GridTemplateAreasError::MismatchedRowLength {
+                row: 3,
+                expected: 2,
+                found: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn areas_must_be_filled_rectangles() {
+        assert_eq!(
+            GridTemplateAreas::parse(&["a b a"]),
+            Err(GridTemplateAreasError::NonRectangular("a".to_string()))
+        );
+        assert_eq!(
+            GridTemplateAreas::parse(&["a a", "a b"]),
+            Err(GridTemplateAreasError::NonRectangular("a".to_string()))
+        );
+    }
+
+    #[test]
+    fn place_sets_the_lines_of_the_named_area() {
+        let areas = GridTemplateAreas::parse(&["a a", "b c"]).unwrap();
+        let mut style = taffy::Style::DEFAULT;
+        assert!(areas.place("a", &mut style));
+        assert_eq!(style.grid_row.start, line(1));
+        assert_eq!(style.grid_row.end, line(2));
+        assert_eq!(style.grid_column.start, line(1));
+        assert_eq!(style.grid_column.end, line(3));
+        assert!(!areas.place("d", &mut style));
+    }
+}
@@ -0,0 +1,214 @@
+//! Named grid lines (the `[name]` syntax of CSS `grid-template-columns` and `grid-template-rows`)
+
+use std::collections::HashMap;
+
+use crate::tree::taffy;
+
+/// A list of grid tracks along with names for the lines between them.
+///
+/// This corresponds to a CSS track list such as `[sidebar-start] 200px [sidebar-end
+/// content-start] 1fr [content-end]`, which would be written as:
+///
+/// ```ignore
+/// GridTrackList::new()
+///     .line("sidebar-start")
+///     .track(points(200.))
+///     .line("sidebar-end")
+///     .line("content-start")
+///     .track(flex(1.))
+///     .line("content-end")
+/// ```
+///
+/// A plain `Vec` of tracks converts into a [`GridTrackList`] with no named lines.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GridTrackList {
+    tracks: Vec<taffy::TrackSizingFunction>,
+    /// Each name along with the number of track definitions which precede it
+    names: Vec<(usize, String)>,
+}
+
+impl GridTrackList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a track to the list
+    pub fn track(mut self, track: taffy::TrackSizingFunction) -> Self {
+        self.tracks.push(track);
+        self
+    }
+
+    /// Names the line after the last track added so far (or the first line if no tracks have been
+    /// added). A line may be given several names.
+    pub fn line(mut self, name: impl Into<String>) -> Self {
+        self.names.push((self.tracks.len(), name.into()));
+        self
+    }
+
+    /// Split the list into Taffy's track definitions and the line index of each name
+    pub(crate) fn into_parts(self) -> (Vec<taffy::TrackSizingFunction>, HashMap<String, i16>) {
+        fn track_count(track: &taffy::TrackSizingFunction) -> i16 {
+            match track {
+                taffy::TrackSizingFunction::Single(_) => 1,
+                taffy::TrackSizingFunction::Repeat(
+                    taffy::GridTrackRepetition::Count(count),
+                    tracks,
+                ) => *count as i16 * tracks.len() as i16,
+                // Auto-repeated tracks are handled separately
+                taffy::TrackSizingFunction::Repeat(_, _) => 0,
+            }
+        }
+
+        // The number of tracks generated by an auto-repetition is not known until layout, so
+        // lines after one are counted backwards from the end of the explicit grid instead.
+        let auto_repetition = self
+            .tracks
+            .iter()
+            .position(|track| track.is_auto_repetition());
+        let mut lines = HashMap::new();
+        for (position, name) in self.names {
+            let index = match auto_repetition {
+                Some(auto_position) if position > auto_position => {
+                    -(self.tracks[position..].iter().map(track_count).sum::<i16>() + 1)
+                }
+                _ => self.tracks[..position].iter().map(track_count).sum::<i16>() + 1,
+            };
+            // As in CSS, a name refers to the first line that it is given to
+            lines.entry(name).or_insert(index);
+        }
+
+        (self.tracks, lines)
+    }
+}
+
+impl From<Vec<taffy::TrackSizingFunction>> for GridTrackList {
+    fn from(tracks: Vec<taffy::TrackSizingFunction>) -> Self {
+        Self {
+            tracks,
+            names: Vec::new(),
+        }
+    }
+}
+
+/// The named lines of a [`Grid`](crate::Grid), used to place children by line name
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GridLineNames {
+    pub(crate) columns: HashMap<String, i16>,
+    pub(crate) rows: HashMap<String, i16>,
+}
+
+impl GridLineNames {
+    /// The placement of the column line with the given name.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no column line has that name.
+    pub fn column_line(&self, name: &str) -> taffy::GridPlacement {
+        match self.columns.get(name) {
+            Some(&index) => taffy::style_helpers::line(index),
+            None => panic!("no grid column line named `{}`", name),
+        }
+    }
+
+    /// The placement of the row line with the given name.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no row line has that name.
+    pub fn row_line(&self, name: &str) -> taffy::GridPlacement {
+        match self.rows.get(name) {
+            Some(&index) => taffy::style_helpers::line(index),
+            None => panic!("no grid row line named `{}`", name),
+        }
+    }
+
+    /// A column placement spanning from the line named `start` to the line named `end`
+    pub fn columns(&self, start: &str, end: &str) -> taffy::Line<taffy::GridPlacement> {
+        taffy::Line {
+            start: self.column_line(start),
+            end: self.column_line(end),
+        }
+    }
+
+    /// A row placement spanning from the line named `start` to the line named `end`
+    pub fn rows(&self, start: &str, end: &str) -> taffy::Line<taffy::GridPlacement> {
+        taffy::Line {
+            start: self.row_line(start),
+            end: self.row_line(end),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::fixed;
+
+    fn single(size: f32) -> taffy::TrackSizingFunction {
+        taffy::TrackSizingFunction::Single(fixed(size))
+    }
+
+    fn repeat(repetition: taffy::GridTrackRepetition, count: usize) -> taffy::TrackSizingFunction {
+        taffy::TrackSizingFunction::Repeat(repetition, vec![fixed(10.0); count])
+    }
+
+    #[test]
+    fn lines_are_numbered_from_one() {
+        let (tracks, lines) = GridTrackList::new()
+            .line("start")
+            .track(single(10.0))
+            .line("middle")
+            .track(single(20.0))
+            .line("end")
+            .into_parts();
+        assert_eq!(tracks, vec![single(10.0), single(20.0)]);
+        assert_eq!(lines["start"], 1);
+        assert_eq!(lines["middle"], 2);
+        assert_eq!(lines["end"], 3);
+    }
+
+    #[test]
+    fn repeated_tracks_are_counted_individually() {
+        let (_, lines) = GridTrackList::new()
+            .track(repeat(taffy::GridTrackRepetition::Count(3), 2))
+            .line("after")
+            .into_parts();
+        assert_eq!(lines["after"], 7);
+    }
+
+    #[test]
+    fn lines_after_an_auto_repetition_count_from_the_end() {
+        let (_, lines) = GridTrackList::new()
+            .line("first")
+            .track(single(10.0))
+            .line("before")
+            .track(repeat(taffy::GridTrackRepetition::AutoFill, 1))
+            .line("after")
+            .track(repeat(taffy::GridTrackRepetition::Count(2), 1))
+            .line("last")
+            .into_parts();
+        assert_eq!(lines["first"], 1);
+        assert_eq!(lines["before"], 2);
+        assert_eq!(lines["after"], -3);
+        assert_eq!(lines["last"], -1);
+    }
+
+    #[test]
+    fn names_refer_to_the_first_line_they_are_given_to() {
+        let (_, lines) = GridTrackList::new()
+            .line("edge")
+            .line("start")
+            .track(single(10.0))
+            .line("edge")
+            .into_parts();
+        assert_eq!(lines["edge"], 1);
+        assert_eq!(lines["start"], 1);
+    }
+
+    #[test]
+    fn plain_track_lists_have_no_names() {
+        let (tracks, lines) = GridTrackList::from(vec![single(10.0)]).into_parts();
+        assert_eq!(tracks, vec![single(10.0)]);
+        assert!(lines.is_empty());
+    }
+}
@@ -1,5 +1,59 @@
+//! [Taffy](https://github.com/DioxusLabs/taffy) layout for the
+//! [Iced](https://github.com/iced-rs/iced) GUI framework: [`Grid`], [`Flex`] and [`Block`]
+//! containers, and a [`TaffyContainer`] which can switch between them.
+//!
+//! ## Baseline alignment
+//!
+//! Containers align their children by their first baselines when their style asks for
+//! `AlignItems::Baseline`. Nested iced_taffy containers report the baseline computed by their
+//! layout, but iced widgets have no way to report one. This includes `text`: iced's renderer does
+//! not expose font metrics, so a `text` child is aligned as if its baseline was its bottom edge.
+//! Wrap such children with [`with_baseline`], using [`text_baseline`] to estimate the baseline of
+//! text of a given size. For a widget which draws its text inside padding, such as a
+//! `text_input`, add the top padding to the estimate.
+
+mod baseline;
+mod block;
+mod cache;
+mod container;
+mod flex;
 mod grid;
+mod grid_areas;
+mod grid_lines;
+mod masonry;
+mod measure;
+mod overlay;
+mod taffy_container;
+#[cfg(test)]
+mod test_helpers;
+mod tree;
+mod virtual_grid;
+mod z_index;
 
+pub use crate::baseline::text_baseline;
+pub use crate::baseline::with_baseline;
+pub use crate::baseline::WithBaseline;
+pub use crate::block::block;
+pub use crate::block::Block;
+pub use crate::cache::with_content_key;
+pub use crate::cache::WithContentKey;
+pub use crate::flex::flex;
+pub use crate::flex::Flex;
 pub use crate::grid::grid;
 pub use crate::grid::Grid;
+pub use crate::grid_areas::GridTemplateAreas;
+pub use crate::grid_areas::GridTemplateAreasError;
+pub use crate::grid_lines::GridLineNames;
+pub use crate::grid_lines::GridTrackList;
+pub use crate::measure::measured;
+pub use crate::measure::Measured;
+pub use crate::measure::TaffyMeasure;
+pub use crate::taffy_container::taffy_container;
+pub use crate::taffy_container::Display;
+pub use crate::taffy_container::TaffyContainer;
+pub use crate::virtual_grid::virtual_grid;
+pub use crate::virtual_grid::RowHeight;
+pub use crate::virtual_grid::VirtualGrid;
+pub use crate::z_index::with_z_index;
+pub use crate::z_index::WithZIndex;
 pub use taffy::style_helpers;
@@ -0,0 +1,787 @@
+//! Masonry layout for [`Grid`](crate::Grid)
+//!
+//! In a masonry grid the columns are ordinary grid tracks but there are no rows. Instead each
+//! child is placed in the column (or run of columns) where it can sit highest, directly below the
+//! children already placed there. This packs children of uneven heights without leaving gaps, as
+//! in the proposed CSS `grid-template-rows: masonry`.
+//!
+//! Taffy does not provide a masonry algorithm, so [`MasonryAlgorithm`] implements the following
+//! subset on top of Taffy's [`LayoutTree`](taffy::LayoutTree) abstraction:
+//!
+//!   - Columns are sized from `grid_template_columns`, which may contain fixed, percentage,
+//!     intrinsic and flexible (`fr`) tracks as well as `repeat()`. Intrinsically sized columns
+//!     are sized as if every auto-placed child spanning a single column were in them.
+//!   - Children may span several columns with `grid_column`, and may be pinned to specific
+//!     columns by giving them a line. Their `grid_row` is ignored.
+//!   - Children stretch to fill the width of their columns unless they have a definite width.
+//!   - Absolutely positioned children are laid out as ordinary children.
+
+use crate::block::{
+    resolve_length_percentage, resolve_length_percentage_auto, ContainerBox, SizeConstraints,
+};
+use crate::tree::taffy;
+
+/// An in-flow child of a masonry container
+struct MasonryItem {
+    node: taffy::NodeId,
+    order: u32,
+    /// The column span of the child, and the first column it must be placed in (if any)
+    span: usize,
+    column: Option<usize>,
+    constraints: SizeConstraints,
+    margin: taffy::Rect<Option<f32>>,
+}
+
+impl MasonryItem {
+    fn horizontal_margin(&self) -> f32 {
+        self.margin.left.unwrap_or(0.0) + self.margin.right.unwrap_or(0.0)
+    }
+}
+
+/// Resolve the column placement of a child into its span and the index of its first column, if
+/// the placement is definite
+fn column_placement(
+    placement: taffy::Line<taffy::GridPlacement>,
+    column_count: usize,
+) -> (usize, Option<usize>) {
+    type Placement = taffy::GridPlacement;
+    // Lines are 1-based, and negative lines count backwards from the end of the grid
+    let line_index = |line: i16| {
+        let index = if line < 0 {
+            column_count as i16 + line + 1
+        } else {
+            line - 1
+        };
+        index.clamp(0, column_count as i16) as usize
+    };
+    let (span, start) = match (placement.start, placement.end) {
+        (Placement::Line(start), Placement::Line(end)) => {
+            let (start, end) = (line_index(start.as_i16()), line_index(end.as_i16()));
+            (start.abs_diff(end).max(1), Some(start.min(end)))
+        }
+        (Placement::Line(start), Placement::Span(span)) => {
+            (span as usize, Some(line_index(start.as_i16())))
+        }
+        (Placement::Line(start), Placement::Auto) => (1, Some(line_index(start.as_i16()))),
+        (Placement::Span(span), Placement::Line(end)) => {
+            let end = line_index(end.as_i16());
+            (span as usize, Some(end.saturating_sub(span as usize)))
+        }
+        (Placement::Auto, Placement::Line(end)) => {
+            (1, Some(line_index(end.as_i16()).saturating_sub(1)))
+        }
+        (Placement::Span(span), _) | (_, Placement::Span(span)) => (span as usize, None),
+        (Placement::Auto, Placement::Auto) => (1, None),
+    };
+    let span = span.clamp(1, column_count);
+    (span, start.map(|start| start.min(column_count - span)))
+}
+
+/// The size of a track used to work out how many times an auto-repeated track list fits: its
+/// maximum size if that is definite, or else its minimum size
+fn track_definite_value(track: &taffy::NonRepeatedTrackSizingFunction, width: Option<f32>) -> f32 {
+    track
+        .max
+        .definite_value(width)
+        .or(track.min.definite_value(width))
+        .unwrap_or(0.0)
+}
+
+/// Expand the column template into a list of individual tracks, repeating `repeat()` track lists
+fn expand_tracks(
+    template: &[taffy::TrackSizingFunction],
+    width: Option<f32>,
+    gap: f32,
+) -> Vec<taffy::NonRepeatedTrackSizingFunction> {
+    let mut tracks = Vec::new();
+    for track in template {
+        match track {
+            taffy::TrackSizingFunction::Single(track) => tracks.push(*track),
+            taffy::TrackSizingFunction::Repeat(
+                taffy::GridTrackRepetition::Count(count),
+                repeated,
+            ) => {
+                for _ in 0..*count {
+                    tracks.extend(repeated.iter().copied());
+                }
+            }
+            taffy::TrackSizingFunction::Repeat(_, repeated) => {
+                // Repeat the track list as many times as fits in the container, and at least once
+                let repetitions = match width {
+                    Some(width) => {
+                        let other_tracks: f32 = template
+                            .iter()
+                            .map(|track| match track {
+                                taffy::TrackSizingFunction::Single(track) => {
+                                    track_definite_value(track, Some(width)) + gap
+                                }
+                                taffy::TrackSizingFunction::Repeat(
+                                    taffy::GridTrackRepetition::Count(count),
+                                    repeated,
+                                ) => {
+                                    *count as f32
+                                        * repeated
+                                            .iter()
+                                            .map(|track| {
+                                                track_definite_value(track, Some(width)) + gap
+                                            })
+                                            .sum::<f32>()
+                                }
+                                taffy::TrackSizingFunction::Repeat(_, _) => 0.0,
+                            })
+                            .sum();
+                        let repetition: f32 = repeated
+                            .iter()
+                            .map(|track| track_definite_value(track, Some(width)) + gap)
+                            .sum();
+                        if repetition > 0.0 {
+                            ((width + gap - other_tracks) / repetition).floor().max(1.0) as usize
+                        } else {
+                            1
+                        }
+                    }
+                    None => 1,
+                };
+                for _ in 0..repetitions {
+                    tracks.extend(repeated.iter().copied());
+                }
+            }
+        }
+    }
+    if tracks.is_empty() {
+        tracks.push(taffy::MinMax {
+            min: taffy::MinTrackSizingFunction::Auto,
+            max: taffy::MaxTrackSizingFunction::Auto,
+        });
+    }
+    tracks
+}
+
+/// The min-content and max-content widths of the items which contribute to a track's size
+#[derive(Debug, Clone, Copy, Default)]
+struct Contribution {
+    min_content: f32,
+    max_content: f32,
+}
+
+impl Contribution {
+    fn include(&mut self, other: Contribution) {
+        self.min_content = self.min_content.max(other.min_content);
+        self.max_content = self.max_content.max(other.max_content);
+    }
+}
+
+/// Measure the width of an item (including its margins) under a min-content and a max-content
+/// constraint
+fn measure_contribution(
+    tree: &mut impl taffy::LayoutTree,
+    item: &MasonryItem,
+    content_size: taffy::Size<Option<f32>>,
+) -> Contribution {
+    let mut measure = |available_width| {
+        let width = item.constraints.width().unwrap_or_else(|| {
+            let size = tree.measure_child_size(
+                item.node,
+                taffy::Size {
+                    width: None,
+                    height: item.constraints.height(),
+                },
+                content_size,
+                taffy::Size {
+                    width: available_width,
+                    height: taffy::AvailableSpace::MaxContent,
+                },
+                taffy::SizingMode::InherentSize,
+            );
+            item.constraints.clamp_width(size.width)
+        });
+        width + item.horizontal_margin()
+    };
+    Contribution {
+        min_content: measure(taffy::AvailableSpace::MinContent),
+        max_content: measure(taffy::AvailableSpace::MaxContent),
+    }
+}
+
+/// Size the columns, following the outline of the CSS grid track sizing algorithm: each track
+/// starts at its minimum size, and then grows towards its maximum size, into flexible tracks,
+/// and finally by stretching `auto` tracks, for as long as there is free space
+fn size_tracks(
+    tracks: &[taffy::NonRepeatedTrackSizingFunction],
+    contributions: &[Contribution],
+    width: Option<f32>,
+    available_width: taffy::AvailableSpace,
+    gap: f32,
+) -> Vec<f32> {
+    let mut base_sizes: Vec<f32> = tracks
+        .iter()
+        .zip(contributions)
+        .map(|(track, contribution)| match track.min {
+            taffy::MinTrackSizingFunction::MaxContent => contribution.max_content,
+            taffy::MinTrackSizingFunction::MinContent | taffy::MinTrackSizingFunction::Auto => {
+                contribution.min_content
+            }
+            fixed => fixed.definite_value(width).unwrap_or(0.0),
+        })
+        .collect();
+    let limits: Vec<Option<f32>> = tracks
+        .iter()
+        .zip(contributions)
+        .zip(base_sizes.iter())
+        .map(|((track, contribution), &base_size)| {
+            let limit = match track.max {
+                taffy::MaxTrackSizingFunction::Fraction(_) => return None,
+                taffy::MaxTrackSizingFunction::MinContent => contribution.min_content,
+                taffy::MaxTrackSizingFunction::MaxContent | taffy::MaxTrackSizingFunction::Auto => {
+                    contribution.max_content
+                }
+                taffy::MaxTrackSizingFunction::FitContent(limit) => contribution
+                    .max_content
+                    .min(resolve_length_percentage(limit, width)),
+                fixed => fixed.definite_value(width).unwrap_or(0.0),
+            };
+            Some(limit.max(base_size))
+        })
+        .collect();
+
+    let gaps = gap * tracks.len().saturating_sub(1) as f32;
+    let free_space = match width {
+        Some(width) => Some(width - gaps - base_sizes.iter().sum::<f32>()),
+        // Under a min-content constraint the tracks do not grow at all
+        None => match available_width {
+            taffy::AvailableSpace::MinContent => Some(0.0),
+            _ => None,
+        },
+    };
+
+    // Grow tracks towards their limits, sharing the free space equally between them. Each pass
+    // either uses up the free space or brings a track to its limit, so there is at most one pass
+    // per track, unless the free space is only reduced to a rounding error.
+    match free_space {
+        Some(mut free_space) => {
+            for _ in 0..tracks.len() {
+                let growable: Vec<usize> = (0..tracks.len())
+                    .filter(|&i| matches!(limits[i], Some(limit) if base_sizes[i] < limit))
+                    .collect();
+                if free_space <= 0.0 || growable.is_empty() {
+                    break;
+                }
+                let share = free_space / growable.len() as f32;
+                for i in growable {
+                    let growth = share.min(limits[i].unwrap() - base_sizes[i]);
+                    base_sizes[i] += growth;
+                    free_space -= growth;
+                }
+            }
+        }
+        None => {
+            for (base_size, limit) in base_sizes.iter_mut().zip(limits.iter()) {
+                if let Some(limit) = limit {
+                    *base_size = *limit;
+                }
+            }
+        }
+    }
+
+    // Expand flexible tracks. When the container's width is known the free space is divided
+    // between them in proportion to their flex factors, without shrinking any track below its
+    // base size. Otherwise they are sized to fit their max-content contributions.
+    let flex_factor = |track: &taffy::NonRepeatedTrackSizingFunction| match track.max {
+        taffy::MaxTrackSizingFunction::Fraction(flex) => Some(flex),
+        _ => None,
+    };
+    let flexible: Vec<usize> = (0..tracks.len())
+        .filter(|&i| flex_factor(&tracks[i]).is_some())
+        .collect();
+    if !flexible.is_empty() {
+        let fraction_size = match width {
+            Some(width) => {
+                let mut inflexible = vec![false; tracks.len()];
+                loop {
+                    let leftover = width
+                        - gaps
+                        - (0..tracks.len())
+                            .filter(|&i| flex_factor(&tracks[i]).is_none() || inflexible[i])
+                            .map(|i| base_sizes[i])
+                            .sum::<f32>();
+                    let flex_sum: f32 = flexible
+                        .iter()
+                        .filter(|&&i| !inflexible[i])
+                        .map(|&i| flex_factor(&tracks[i]).unwrap())
+                        .sum::<f32>()
+                        .max(1.0);
+                    let fraction_size = leftover.max(0.0) / flex_sum;
+                    let newly_inflexible: Vec<usize> = flexible
+                        .iter()
+                        .copied()
+                        .filter(|&i| {
+                            !inflexible[i]
+                                && fraction_size * flex_factor(&tracks[i]).unwrap() < base_sizes[i]
+                        })
+                        .collect();
+                    if newly_inflexible.is_empty() {
+                        break fraction_size;
+                    }
+                    for i in newly_inflexible {
+                        inflexible[i] = true;
+                    }
+                }
+            }
+            None => flexible
+                .iter()
+                .map(|&i| {
+                    let flex = flex_factor(&tracks[i]).unwrap();
+                    let contribution = match available_width {
+                        taffy::AvailableSpace::MinContent => contributions[i].min_content,
+                        _ => contributions[i].max_content,
+                    };
+                    if flex > 1.0 {
+                        contribution / flex
+                    } else {
+                        contribution
+                    }
+                })
+                .fold(0.0, f32::max),
+        };
+        for i in flexible {
+            base_sizes[i] = base_sizes[i].max(fraction_size * flex_factor(&tracks[i]).unwrap());
+        }
+    }
+
+    // Stretch `auto` tracks to fill any remaining space
+    if let Some(free_space) = width.map(|width| width - gaps - base_sizes.iter().sum::<f32>()) {
+        let auto_tracks: Vec<usize> = (0..tracks.len())
+            .filter(|&i| tracks[i].max == taffy::MaxTrackSizingFunction::Auto)
+            .collect();
+        if free_space > 0.0 && !auto_tracks.is_empty() {
+            let share = free_space / auto_tracks.len() as f32;
+            for i in auto_tracks {
+                base_sizes[i] += share;
+            }
+        }
+    }
+
+    base_sizes
+}
+
+/// An implementation of masonry layout which uses Taffy's [`LayoutTree`](taffy::LayoutTree)
+pub(crate) struct MasonryAlgorithm;
+
+impl ::taffy::LayoutAlgorithm for MasonryAlgorithm {
+    const NAME: &'static str = "MASONRY";
+
+    fn measure_size(
+        tree: &mut impl taffy::LayoutTree,
+        node: taffy::NodeId,
+        known_dimensions: taffy::Size<Option<f32>>,
+        parent_size: taffy::Size<Option<f32>>,
+        available_space: taffy::Size<taffy::AvailableSpace>,
+        sizing_mode: taffy::SizingMode,
+    ) -> taffy::Size<f32> {
+        compute(
+            tree,
+            node,
+            known_dimensions,
+            parent_size,
+            available_space,
+            sizing_mode,
+            taffy::RunMode::ComputeSize,
+        )
+        .size
+    }
+
+    fn perform_layout(
+        tree: &mut impl taffy::LayoutTree,
+        node: taffy::NodeId,
+        known_dimensions: taffy::Size<Option<f32>>,
+        parent_size: taffy::Size<Option<f32>>,
+        available_space: taffy::Size<taffy::AvailableSpace>,
+        sizing_mode: taffy::SizingMode,
+    ) -> taffy::SizeAndBaselines {
+        compute(
+            tree,
+            node,
+            known_dimensions,
+            parent_size,
+            available_space,
+            sizing_mode,
+            taffy::RunMode::PeformLayout,
+        )
+    }
+}
+
+fn compute(
+    tree: &mut impl taffy::LayoutTree,
+    node: taffy::NodeId,
+    known_dimensions: taffy::Size<Option<f32>>,
+    parent_size: taffy::Size<Option<f32>>,
+    available_space: taffy::Size<taffy::AvailableSpace>,
+    sizing_mode: taffy::SizingMode,
+    run_mode: taffy::RunMode,
+) -> taffy::SizeAndBaselines {
+    let style = tree.style(node);
+
+    let ContainerBox {
+        constraints,
+        padding,
+        border,
+        padding_border,
+    } = ContainerBox::resolve(style, parent_size, sizing_mode);
+
+    let known_width =
+        known_dimensions
+            .width
+            .or(constraints.width())
+            .or(match available_space.width {
+                taffy::AvailableSpace::Definite(width) => Some(constraints.clamp_width(width)),
+                _ => None,
+            });
+    let content_width = known_width.map(|width| (width - padding_border.width).max(0.0));
+    let known_height = known_dimensions.height.or(constraints.height());
+    let content_height = known_height.map(|height| (height - padding_border.height).max(0.0));
+    let content_size = taffy::Size {
+        width: content_width,
+        height: content_height,
+    };
+    let column_gap = resolve_length_percentage(style.gap.width, content_width);
+    let row_gap = resolve_length_percentage(style.gap.height, content_height);
+
+    let tracks = expand_tracks(&style.grid_template_columns, content_width, column_gap);
+    let column_count = tracks.len();
+
+    // Collect the visible children
+    let mut items = Vec::new();
+    let mut hidden_items = Vec::new();
+    for (order, child) in tree.children(node).enumerate() {
+        let child_style = tree.style(child);
+        if child_style.display == taffy::Display::None {
+            hidden_items.push((child, order as u32));
+        } else {
+            let (span, column) = column_placement(child_style.grid_column, column_count);
+            items.push(MasonryItem {
+                node: child,
+                order: order as u32,
+                span,
+                column,
+                constraints: SizeConstraints::resolve(child_style, content_size),
+                margin: child_style
+                    .margin
+                    .map(|m| resolve_length_percentage_auto(m, content_width)),
+            });
+        }
+    }
+
+    // Size the columns from the intrinsic widths of the children which span a single column
+    let mut contributions = vec![Contribution::default(); column_count];
+    if tracks.iter().any(|track| {
+        track.min.is_intrinsic() || track.max.is_intrinsic() || track.max.is_flexible()
+    }) {
+        for item in items.iter().filter(|item| item.span == 1) {
+            let contribution = measure_contribution(tree, item, content_size);
+            match item.column {
+                Some(column) => contributions[column].include(contribution),
+                None => contributions
+                    .iter_mut()
+                    .for_each(|track| track.include(contribution)),
+            }
+        }
+    }
+    let column_widths = size_tracks(
+        &tracks,
+        &contributions,
+        content_width,
+        available_space.width,
+        column_gap,
+    );
+    let mut column_offsets = Vec::with_capacity(column_count);
+    let mut x = padding.left + border.left;
+    for column_width in column_widths.iter() {
+        column_offsets.push(x);
+        x += column_width + column_gap;
+    }
+    let width = known_width.unwrap_or_else(|| {
+        let columns_width =
+            column_widths.iter().sum::<f32>() + column_gap * column_count.saturating_sub(1) as f32;
+        constraints.clamp_width(columns_width + padding_border.width)
+    });
+
+    // Place each child in the run of columns where it can sit highest
+    let mut column_heights = vec![0.0f32; column_count];
+    let mut first_baseline = None;
+    for (index, item) in items.iter().enumerate() {
+        let column = item.column.unwrap_or_else(|| {
+            (0..=column_count - item.span)
+                .map(|start| {
+                    let top = column_heights[start..start + item.span]
+                        .iter()
+                        .copied()
+                        .fold(0.0, f32::max);
+                    (start, top)
+                })
+                .fold((0, f32::INFINITY), |lowest, candidate| {
+                    if candidate.1 < lowest.1 {
+                        candidate
+                    } else {
+                        lowest
+                    }
+                })
+                .0
+        });
+        let columns = column..column + item.span;
+        let top = column_heights[columns.clone()]
+            .iter()
+            .copied()
+            .fold(0.0, f32::max);
+        let area_width = column_widths[columns.clone()].iter().sum::<f32>()
+            + column_gap * (item.span - 1) as f32;
+
+        let margin_top = item.margin.top.unwrap_or(0.0);
+        let margin_bottom = item.margin.bottom.unwrap_or(0.0);
+        let margin_left = item.margin.left.unwrap_or(0.0);
+        let child_width = item.constraints.width().unwrap_or_else(|| {
+            item.constraints
+                .clamp_width(area_width - item.horizontal_margin())
+        });
+        let child_known_dimensions = taffy::Size {
+            width: Some(child_width),
+            height: item.constraints.height(),
+        };
+        let child_available_space = taffy::Size {
+            width: taffy::AvailableSpace::Definite(child_width),
+            height: taffy::AvailableSpace::MaxContent,
+        };
+        let (child_height, child_baseline) = match run_mode {
+            taffy::RunMode::PeformLayout => {
+                let size_and_baselines = tree.perform_child_layout(
+                    item.node,
+                    child_known_dimensions,
+                    content_size,
+                    child_available_space,
+                    taffy::SizingMode::InherentSize,
+                );
+                (
+                    size_and_baselines.size.height,
+                    size_and_baselines.first_baselines.y,
+                )
+            }
+            _ => {
+                let size = tree.measure_child_size(
+                    item.node,
+                    child_known_dimensions,
+                    content_size,
+                    child_available_space,
+                    taffy::SizingMode::InherentSize,
+                );
+                (size.height, None)
+            }
+        };
+        let child_height = item
+            .constraints
+            .height()
+            .unwrap_or_else(|| item.constraints.clamp_height(child_height));
+        let y = padding.top + border.top + top + margin_top;
+
+        if matches!(run_mode, taffy::RunMode::PeformLayout) {
+            *tree.layout_mut(item.node) = taffy::Layout {
+                order: item.order,
+                size: taffy::Size {
+                    width: child_width,
+                    height: child_height,
+                },
+                location: taffy::Point {
+                    x: column_offsets[column] + margin_left,
+                    y,
+                },
+            };
+        }
+
+        // The container's first baseline is that of its first child
+        if index == 0 {
+            first_baseline = child_baseline.map(|baseline| y + baseline);
+        }
+
+        let bottom = top + margin_top + child_height + margin_bottom + row_gap;
+        for height in column_heights[columns].iter_mut() {
+            *height = bottom;
+        }
+    }
+
+    let content_bottom = column_heights.iter().copied().fold(0.0, f32::max);
+    let height = known_height.unwrap_or_else(|| {
+        let items_height = (content_bottom - row_gap).max(0.0);
+        constraints.clamp_height(items_height + padding_border.height)
+    });
+
+    if matches!(run_mode, taffy::RunMode::PeformLayout) {
+        for (child, order) in hidden_items {
+            *tree.layout_mut(child) = taffy::Layout {
+                order,
+                ..taffy::NULL_LAYOUT
+            };
+        }
+    }
+
+    taffy::SizeAndBaselines {
+        size: taffy::Size { width, height },
+        first_baselines: taffy::Point {
+            x: None,
+            y: first_baseline,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{auto, fixed, fr, line, span};
+
+    fn placement(
+        start: taffy::GridPlacement,
+        end: taffy::GridPlacement,
+    ) -> taffy::Line<taffy::GridPlacement> {
+        taffy::Line { start, end }
+    }
+
+    fn contribution(min_content: f32, max_content: f32) -> Contribution {
+        Contribution {
+            min_content,
+            max_content,
+        }
+    }
+
+    #[test]
+    fn auto_placed_children_have_no_column() {
+        let auto = taffy::GridPlacement::Auto;
+        assert_eq!(column_placement(placement(auto, auto), 4), (1, None));
+        assert_eq!(column_placement(placement(span(2), auto), 4), (2, None));
+        assert_eq!(column_placement(placement(auto, span(3)), 4), (3, None));
+    }
+
+    #[test]
+    fn lines_resolve_to_columns() {
+        assert_eq!(
+            column_placement(placement(line(2), line(4)), 4),
+            (2, Some(1))
+        );
+        assert_eq!(
+            column_placement(placement(line(4), line(2)), 4),
+            (2, Some(1))
+        );
+        assert_eq!(
+            column_placement(placement(line(3), taffy::GridPlacement::Auto), 4),
+            (1, Some(2))
+        );
+        assert_eq!(
+            column_placement(placement(taffy::GridPlacement::Auto, line(3)), 4),
+            (1, Some(1))
+        );
+        assert_eq!(
+            column_placement(placement(span(2), line(5)), 4),
+            (2, Some(2))
+        );
+    }
+
+    #[test]
+    fn negative_lines_count_from_the_end() {
+        assert_eq!(
+            column_placement(placement(line(-3), line(-1)), 4),
+            (2, Some(2))
+        );
+        assert_eq!(
+            column_placement(placement(line(1), line(-1)), 4),
+            (4, Some(0))
+        );
+    }
+
+    #[test]
+    fn placements_are_clamped_to_the_columns() {
+        assert_eq!(
+            column_placement(placement(span(6), taffy::GridPlacement::Auto), 4),
+            (4, None)
+        );
+        assert_eq!(
+            column_placement(placement(line(4), span(2)), 4),
+            (2, Some(2))
+        );
+        assert_eq!(
+            column_placement(placement(line(9), line(12)), 4),
+            (1, Some(3))
+        );
+    }
+
+    #[test]
+    fn fixed_tracks_keep_their_size() {
+        let sizes = size_tracks(
+            &[fixed(50.0), fixed(70.0)],
+            &[contribution(0.0, 0.0); 2],
+            Some(500.0),
+            taffy::AvailableSpace::Definite(500.0),
+            10.0,
+        );
+        assert_eq!(sizes, vec![50.0, 70.0]);
+    }
+
+    #[test]
+    fn flexible_tracks_share_the_free_space() {
+        let sizes = size_tracks(
+            &[fixed(100.0), fr(1.0), fr(2.0)],
+            &[contribution(0.0, 0.0); 3],
+            Some(420.0),
+            taffy::AvailableSpace::Definite(420.0),
+            10.0,
+        );
+        assert_eq!(sizes, vec![100.0, 100.0, 200.0]);
+    }
+
+    #[test]
+    fn flexible_tracks_larger_than_their_share_are_frozen_as_inflexible() {
+        let sizes = size_tracks(
+            &[fr(1.0), fr(1.0)],
+            &[contribution(250.0, 250.0), contribution(0.0, 0.0)],
+            Some(300.0),
+            taffy::AvailableSpace::Definite(300.0),
+            0.0,
+        );
+        assert_eq!(sizes, vec![250.0, 50.0]);
+    }
+
+    #[test]
+    fn flexible_tracks_fit_their_content_without_a_width() {
+        let sizes = size_tracks(
+            &[fr(1.0), fr(2.0)],
+            &[contribution(10.0, 40.0), contribution(20.0, 100.0)],
+            None,
+            taffy::AvailableSpace::MaxContent,
+            0.0,
+        );
+        assert_eq!(sizes, vec![50.0, 100.0]);
+    }
+
+    #[test]
+    fn auto_tracks_stretch_into_the_remaining_space() {
+        let sizes = size_tracks(
+            &[auto(), fixed(50.0)],
+            &[contribution(20.0, 30.0), contribution(0.0, 0.0)],
+            Some(200.0),
+            taffy::AvailableSpace::Definite(200.0),
+            0.0,
+        );
+        assert_eq!(sizes, vec![150.0, 50.0]);
+    }
+
+    #[test]
+    fn growth_stops_when_only_a_rounding_error_is_left() {
+        let sizes = size_tracks(
+            &[auto(), auto(), auto()],
+            &[
+                contribution(0.0, 0.1),
+                contribution(0.0, 0.7),
+                contribution(0.0, 1e7),
+            ],
+            Some(100.3),
+            taffy::AvailableSpace::Definite(100.3),
+            0.1,
+        );
+        assert!((sizes.iter().sum::<f32>() - 100.1).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,328 @@
+//! Reporting intrinsic sizes to Taffy from custom widgets
+//!
+//! iced's [`Widget::measure`] can only resolve a size within some limits, so iced_taffy has to
+//! probe ordinary widgets to discover their min-content and max-content sizes. Widgets which know
+//! their intrinsic sizes can instead implement [`TaffyMeasure`] and be wrapped with [`measured`].
+//!
+//! As with baselines, the sizes travel through a side channel: while a Taffy container measures
+//! or lays out a child it publishes the constraints that Taffy placed on the child, and a
+//! [`Measured`] widget which receives the limits that the constraints were translated into
+//! answers from its [`TaffyMeasure`] implementation instead of from [`Widget::measure`].
+//!
+//! Nested iced_taffy containers answer in the same way, by running their own layout algorithm
+//! with the original constraints. This avoids the rounding of the limits and keeps Taffy's
+//! min-content and max-content constraints intact across container boundaries.
+
+use std::cell::Cell;
+
+use iced_native::event::{self, Event};
+use iced_native::renderer::Renderer;
+use iced_native::widget::{tree, Operation, Tree};
+use iced_native::{layout, mouse, overlay, renderer};
+use iced_native::{Clipboard, Element, Layout, Length, Point, Rectangle, Shell, Size, Widget};
+
+use crate::baseline::{report_first_baseline, take_first_baseline};
+use crate::tree::taffy;
+
+/// Intrinsic sizing for widgets laid out by iced_taffy containers.
+///
+/// Add a widget implementing this trait to a container with [`measured`] to have Taffy size it
+/// from its intrinsic sizes, for example in `auto`, `min-content` and `max-content` grid tracks.
+pub trait TaffyMeasure<R> {
+    /// The size of the widget when it is as narrow as it can be without its content overflowing,
+    /// given any dimensions which are already known
+    fn min_content_size(
+        &self,
+        renderer: &R,
+        known_dimensions: taffy::Size<Option<f32>>,
+    ) -> taffy::Size<f32>;
+
+    /// The size of the widget when it is given as much space as it can use, given any dimensions
+    /// which are already known
+    fn max_content_size(
+        &self,
+        renderer: &R,
+        known_dimensions: taffy::Size<Option<f32>>,
+    ) -> taffy::Size<f32>;
+
+    /// The offset of the widget's first baseline from its top edge when it has the given size,
+    /// if it has one
+    fn first_baseline(&self, _renderer: &R, _size: taffy::Size<f32>) -> Option<f32> {
+        None
+    }
+
+    /// The ratio of the widget's width to its height, if it should keep one. This is used to
+    /// derive an unknown dimension from a known one.
+    fn aspect_ratio(&self) -> Option<f32> {
+        None
+    }
+
+    /// The size of the widget under the constraints that Taffy places on it.
+    ///
+    /// By default, a dimension which is not known is derived from the other through the
+    /// [`aspect_ratio`](Self::aspect_ratio), and otherwise the width fits the available space
+    /// (clamped between the min-content and max-content widths), and the height is the
+    /// max-content height at that width.
+    fn size(
+        &self,
+        renderer: &R,
+        known_dimensions: taffy::Size<Option<f32>>,
+        available_space: taffy::Size<taffy::AvailableSpace>,
+    ) -> taffy::Size<f32> {
+        let mut known_dimensions = known_dimensions;
+        if let Some(ratio) = self.aspect_ratio() {
+            match (known_dimensions.width, known_dimensions.height) {
+                (Some(width), None) => known_dimensions.height = Some(width / ratio),
+                (None, Some(height)) => known_dimensions.width = Some(height * ratio),
+                _ => {}
+            }
+        }
+
+        let width = known_dimensions
+            .width
+            .unwrap_or_else(|| match available_space.width {
+                taffy::AvailableSpace::MinContent => {
+                    self.min_content_size(renderer, known_dimensions).width
+                }
+                taffy::AvailableSpace::MaxContent => {
+                    self.max_content_size(renderer, known_dimensions).width
+                }
+                taffy::AvailableSpace::Definite(available_width) => {
+                    let min_content = self.min_content_size(renderer, known_dimensions).width;
+                    let max_content = self.max_content_size(renderer, known_dimensions).width;
+                    max_content.min(available_width).max(min_content)
+                }
+            });
+        let height = known_dimensions
+            .height
+            .or(self.aspect_ratio().map(|ratio| width / ratio))
+            .unwrap_or_else(|| {
+                let known_dimensions = taffy::Size {
+                    width: Some(width),
+                    height: None,
+                };
+                self.max_content_size(renderer, known_dimensions).height
+            });
+
+        taffy::Size { width, height }
+    }
+}
+
+/// The constraints placed on the child currently being measured or laid out
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct IntrinsicQuery {
+    /// The limits that the constraints were translated into
+    min: Size,
+    max: Size,
+    pub(crate) known_dimensions: taffy::Size<Option<f32>>,
+    pub(crate) parent_size: taffy::Size<Option<f32>>,
+    pub(crate) available_space: taffy::Size<taffy::AvailableSpace>,
+    pub(crate) sizing_mode: taffy::SizingMode,
+}
+
+thread_local! {
+    static INTRINSIC_QUERY: Cell<Option<IntrinsicQuery>> = Cell::new(None);
+}
+
+/// Run `f`, which measures or lays out a child within `limits`, while publishing the constraints
+/// which the limits were translated from. Also returns whether the child answered, by being a
+/// [`Measured`] widget or a nested container.
+pub(crate) fn with_intrinsic_query<T>(
+    limits: &layout::Limits,
+    known_dimensions: taffy::Size<Option<f32>>,
+    parent_size: taffy::Size<Option<f32>>,
+    available_space: taffy::Size<taffy::AvailableSpace>,
+    sizing_mode: taffy::SizingMode,
+    f: impl FnOnce() -> T,
+) -> (T, bool) {
+    let query = IntrinsicQuery {
+        min: limits.min(),
+        max: limits.max(),
+        known_dimensions,
+        parent_size,
+        available_space,
+        sizing_mode,
+    };
+    // Nested containers publish their own queries while `f` runs, so restore the enclosing
+    // container's query afterwards
+    let previous = INTRINSIC_QUERY.with(|cell| cell.replace(Some(query)));
+    let result = f();
+    let answered = INTRINSIC_QUERY
+        .with(|cell| cell.replace(previous))
+        .is_none();
+    (result, answered)
+}
+
+/// Take the published constraints if they were translated into `limits`, which is only the case
+/// for the child that they were published for (or a widget which passes its limits on unchanged)
+pub(crate) fn take_intrinsic_query(limits: &layout::Limits) -> Option<IntrinsicQuery> {
+    INTRINSIC_QUERY.with(|cell| match cell.get() {
+        Some(query) if query.min == limits.min() && query.max == limits.max() => cell.take(),
+        _ => None,
+    })
+}
+
+/// A widget which is sized by Taffy from its [`TaffyMeasure`] implementation
+pub struct Measured<W> {
+    widget: W,
+}
+
+impl<W> Measured<W> {
+    /// Creates a [`Measured`] widget
+    pub fn new(widget: W) -> Self {
+        Self { widget }
+    }
+}
+
+/// Have Taffy size `widget` from its [`TaffyMeasure`] implementation when it is laid out by an
+/// iced_taffy container
+pub fn measured<W>(widget: W) -> Measured<W> {
+    Measured::new(widget)
+}
+
+impl<Msg, R, W> Widget<Msg, R> for Measured<W>
+where
+    R: Renderer,
+    W: Widget<Msg, R> + TaffyMeasure<R>,
+{
+    fn tag(&self) -> tree::Tag {
+        self.widget.tag()
+    }
+
+    fn state(&self) -> tree::State {
+        self.widget.state()
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        self.widget.children()
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        self.widget.diff(tree)
+    }
+
+    fn width(&self) -> Length {
+        self.widget.width()
+    }
+
+    fn height(&self) -> Length {
+        self.widget.height()
+    }
+
+    fn measure(&mut self, renderer: &R, limits: &layout::Limits) -> Size {
+        match take_intrinsic_query(limits) {
+            Some(query) => {
+                let size =
+                    self.widget
+                        .size(renderer, query.known_dimensions, query.available_space);
+                Size::new(size.width, size.height)
+            }
+            None => self.widget.measure(renderer, limits),
+        }
+    }
+
+    fn layout(&mut self, renderer: &R, limits: &layout::Limits) -> layout::Node {
+        let Some(query) = take_intrinsic_query(limits) else {
+            let node = self.widget.layout(renderer, limits);
+            let first_baseline = take_first_baseline(&self.widget);
+            report_first_baseline(&*self, first_baseline);
+            return node;
+        };
+        let size = self
+            .widget
+            .size(renderer, query.known_dimensions, query.available_space);
+        let iced_size = Size::new(size.width, size.height);
+
+        // Lay the widget out at exactly the size it asked for
+        let node = self
+            .widget
+            .layout(renderer, &layout::Limits::new(iced_size, iced_size));
+        let first_baseline = self.widget.first_baseline(renderer, size);
+        report_first_baseline(&*self, first_baseline);
+        layout::Node::with_children(iced_size, node.into_children())
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &R,
+        operation: &mut dyn Operation<Msg>,
+    ) {
+        self.widget.operate(tree, layout, renderer, operation)
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &R,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Msg>,
+    ) -> event::Status {
+        self.widget.on_event(
+            tree,
+            event,
+            layout,
+            cursor_position,
+            renderer,
+            clipboard,
+            shell,
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+        renderer: &R,
+    ) -> mouse::Interaction {
+        self.widget
+            .mouse_interaction(tree, layout, cursor_position, viewport, renderer)
+    }
+
+    fn draw(
+        &mut self,
+        tree: &Tree,
+        renderer: &mut R,
+        theme: &R::Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+    ) {
+        self.widget.draw(
+            tree,
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor_position,
+            viewport,
+        )
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'_>,
+        renderer: &R,
+    ) -> Option<overlay::Element<'b, Msg, R>> {
+        self.widget.overlay(tree, layout, renderer)
+    }
+}
+
+impl<'a, Msg, R, W> From<Measured<W>> for Element<'a, Msg, R>
+where
+    Msg: 'a,
+    R: Renderer + 'a,
+    W: Widget<Msg, R> + TaffyMeasure<R> + 'a,
+{
+    fn from(measured: Measured<W>) -> Self {
+        Self::new(measured)
+    }
+}
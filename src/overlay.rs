@@ -0,0 +1,117 @@
+//! Combining the overlays of a container's children
+
+use iced_native::event::{self, Event};
+use iced_native::renderer::Renderer;
+use iced_native::widget::Operation;
+use iced_native::{layout, mouse, overlay, renderer};
+use iced_native::{Clipboard, Layout, Point, Rectangle, Shell, Size};
+
+/// An overlay which shows the overlays of several children at once.
+///
+/// Like the children of a container, later overlays are drawn over earlier ones, and are sent
+/// events and asked for a mouse cursor first.
+pub(crate) struct OverlayGroup<'a, Msg, R: Renderer> {
+    children: Vec<overlay::Element<'a, Msg, R>>,
+}
+
+impl<'a, Msg, R> OverlayGroup<'a, Msg, R>
+where
+    Msg: 'a,
+    R: Renderer + 'a,
+{
+    /// Combine `children` into a single overlay, if there are any
+    pub(crate) fn combine(
+        mut children: Vec<overlay::Element<'a, Msg, R>>,
+    ) -> Option<overlay::Element<'a, Msg, R>> {
+        match children.len() {
+            0 => None,
+            1 => children.pop(),
+            // Each child keeps its own position, so the group itself sits at the origin
+            _ => Some(overlay::Element::new(
+                Point::ORIGIN,
+                Box::new(OverlayGroup { children }),
+            )),
+        }
+    }
+}
+
+impl<'a, Msg, R: Renderer> overlay::Overlay<Msg, R> for OverlayGroup<'a, Msg, R> {
+    fn layout(&self, renderer: &R, bounds: Size, position: Point) -> layout::Node {
+        let translation = position - Point::ORIGIN;
+        layout::Node::with_children(
+            bounds,
+            self.children
+                .iter()
+                .map(|child| child.layout(renderer, bounds, translation))
+                .collect(),
+        )
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut R,
+        theme: &R::Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+    ) {
+        for (child, layout) in self.children.iter().zip(layout.children()) {
+            child.draw(renderer, theme, style, layout, cursor_position);
+        }
+    }
+
+    fn operate(&mut self, layout: Layout<'_>, renderer: &R, operation: &mut dyn Operation<Msg>) {
+        operation.container(None, &mut |operation| {
+            self.children
+                .iter_mut()
+                .zip(layout.children())
+                .for_each(|(child, layout)| child.operate(layout, renderer, operation))
+        });
+    }
+
+    fn on_event(
+        &mut self,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &R,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Msg>,
+    ) -> event::Status {
+        let layouts: Vec<Layout<'_>> = layout.children().collect();
+        let mut status = event::Status::Ignored;
+        for (child, layout) in self.children.iter_mut().zip(layouts).rev() {
+            status = child.on_event(
+                event.clone(),
+                layout,
+                cursor_position,
+                renderer,
+                clipboard,
+                shell,
+            );
+            if status == event::Status::Captured {
+                break;
+            }
+        }
+        status
+    }
+
+    fn mouse_interaction(
+        &self,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+        renderer: &R,
+    ) -> mouse::Interaction {
+        let layouts: Vec<Layout<'_>> = layout.children().collect();
+        self.children
+            .iter()
+            .zip(layouts)
+            .rev()
+            .map(|(child, layout)| {
+                child.mouse_interaction(layout, cursor_position, viewport, renderer)
+            })
+            .find(|&interaction| interaction != mouse::Interaction::default())
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,147 @@
+//! A widget whose layout algorithm is chosen at runtime from its display mode
+
+use iced_native::renderer::Renderer;
+
+use crate::container::{container_builders, container_widget, Algorithm, Container};
+use crate::tree::taffy;
+
+/// The layout mode of a [`TaffyContainer`].
+///
+/// This extends Taffy's [`Display`](taffy::Display) with a [`Block`](Display::Block) mode,
+/// which is implemented by iced_taffy rather than by Taffy itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Display {
+    Grid,
+    #[default]
+    Flex,
+    Block,
+    None,
+}
+
+impl From<taffy::Display> for Display {
+    fn from(display: taffy::Display) -> Self {
+        match display {
+            taffy::Display::Grid => Display::Grid,
+            taffy::Display::Flex => Display::Flex,
+            taffy::Display::None => Display::None,
+        }
+    }
+}
+
+impl Display {
+    fn algorithm(self) -> Algorithm {
+        match self {
+            Display::Grid => Algorithm::Grid,
+            Display::Flex => Algorithm::Flex,
+            Display::Block => Algorithm::Block,
+            Display::None => Algorithm::None,
+        }
+    }
+
+    /// The closest equivalent Taffy display mode, used by the parent's layout algorithm
+    fn to_taffy(self) -> taffy::Display {
+        match self {
+            Display::Grid => taffy::Display::Grid,
+            Display::Flex | Display::Block => taffy::Display::Flex,
+            Display::None => taffy::Display::None,
+        }
+    }
+}
+
+/// A container which lays out its children using whichever algorithm its [`Display`] selects.
+///
+/// Setting `style.display` from [`TaffyContainer::style`] switches between grid, flexbox and
+/// `None`. Block layout is selected with [`TaffyContainer::display`], and overrides
+/// `style.display` until another display mode is selected with it.
+pub struct TaffyContainer<'a, Msg, R: Renderer> {
+    /// Whether block layout was selected, which Taffy's `style.display` cannot express
+    block: bool,
+    container: Container<'a, Msg, R>,
+}
+
+impl<'a, Msg, R: Renderer> TaffyContainer<'a, Msg, R> {
+    pub fn new() -> Self {
+        Self {
+            block: false,
+            container: Container::new(taffy::Style::DEFAULT),
+        }
+    }
+
+    /// Sets the layout algorithm used to position the children of the [`TaffyContainer`]
+    pub fn display(mut self, display: Display) -> Self {
+        self.block = display == Display::Block;
+        self.container.style.display = display.to_taffy();
+        self
+    }
+
+    pub fn with_columns(mut self, columns: Vec<taffy::TrackSizingFunction>) -> Self {
+        self.container.style.grid_template_columns = columns;
+        self
+    }
+
+    pub fn with_rows(mut self, rows: Vec<taffy::TrackSizingFunction>) -> Self {
+        self.container.style.grid_template_rows = rows;
+        self
+    }
+
+    pub fn direction(mut self, direction: taffy::FlexDirection) -> Self {
+        self.container.style.flex_direction = direction;
+        self
+    }
+
+    pub fn wrap(mut self, wrap: taffy::FlexWrap) -> Self {
+        self.container.style.flex_wrap = wrap;
+        self
+    }
+
+    pub fn justify_content(mut self, justify_content: taffy::JustifyContent) -> Self {
+        self.container.style.justify_content = Some(justify_content);
+        self
+    }
+
+    pub fn align_items(mut self, align_items: taffy::AlignItems) -> Self {
+        self.container.style.align_items = Some(align_items);
+        self
+    }
+
+    pub fn align_content(mut self, align_content: taffy::AlignContent) -> Self {
+        self.container.style.align_content = Some(align_content);
+        self
+    }
+
+    pub fn with_column_gap(mut self, gap: taffy::LengthPercentage) -> Self {
+        self.container.style.gap.width = gap;
+        self
+    }
+
+    pub fn with_row_gap(mut self, gap: taffy::LengthPercentage) -> Self {
+        self.container.style.gap.height = gap;
+        self
+    }
+
+    /// Applies `callback` to the style of the [`TaffyContainer`]. Setting `style.display` switches
+    /// between grid, flexbox and `None`, unless block layout was selected with
+    /// [`TaffyContainer::display`], in which case `style.display` is left as it was.
+    pub fn style(mut self, mut callback: impl FnMut(&mut taffy::Style)) -> Self {
+        callback(&mut self.container.style);
+        if self.block {
+            self.container.style.display = Display::Block.to_taffy();
+        }
+        self
+    }
+
+    fn algorithm(&self) -> Algorithm {
+        if self.block {
+            Algorithm::Block
+        } else {
+            Display::from(self.container.style.display).algorithm()
+        }
+    }
+}
+
+container_builders!(TaffyContainer);
+container_widget!(TaffyContainer);
+
+pub fn taffy_container<'a, Msg, R: Renderer>() -> TaffyContainer<'a, Msg, R> {
+    TaffyContainer::new()
+}
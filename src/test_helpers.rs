@@ -0,0 +1,34 @@
+//! Helpers shared by the unit tests
+
+use crate::tree::taffy;
+
+pub(crate) fn line(index: i16) -> taffy::GridPlacement {
+    taffy::style_helpers::line(index)
+}
+
+pub(crate) fn span(span: u16) -> taffy::GridPlacement {
+    taffy::style_helpers::span(span)
+}
+
+/// A track of exactly `size` points
+pub(crate) fn fixed(size: f32) -> taffy::NonRepeatedTrackSizingFunction {
+    taffy::MinMax {
+        min: taffy::MinTrackSizingFunction::Fixed(taffy::LengthPercentage::Points(size)),
+        max: taffy::MaxTrackSizingFunction::Fixed(taffy::LengthPercentage::Points(size)),
+    }
+}
+
+/// A flexible track with the given flex factor
+pub(crate) fn fr(flex: f32) -> taffy::NonRepeatedTrackSizingFunction {
+    taffy::MinMax {
+        min: taffy::MinTrackSizingFunction::Auto,
+        max: taffy::MaxTrackSizingFunction::Fraction(flex),
+    }
+}
+
+pub(crate) fn auto() -> taffy::NonRepeatedTrackSizingFunction {
+    taffy::MinMax {
+        min: taffy::MinTrackSizingFunction::Auto,
+        max: taffy::MaxTrackSizingFunction::Auto,
+    }
+}
@@ -0,0 +1,497 @@
+//! The `LayoutTree` adapter which allows Taffy's algorithms to lay out iced children
+
+use iced_native::layout::Limits;
+use iced_native::renderer::Renderer;
+use iced_native::{Element, Length, Widget};
+
+use crate::baseline::{clear_first_baseline, take_first_baseline};
+use crate::grid::Grid;
+use crate::measure::with_intrinsic_query;
+
+pub(crate) mod taffy {
+    pub use ::taffy::layout::{Layout, RunMode, SizeAndBaselines, SizingMode};
+    pub use ::taffy::*;
+    pub use taffy::cache::Cache;
+    pub use taffy::geometry::*;
+    pub use taffy::prelude::*;
+
+    pub const NULL_LAYOUT: Layout = Layout {
+        order: 0,
+        size: Size::ZERO,
+        location: Point::ZERO,
+    };
+}
+
+pub(crate) fn f32_to_opt(input: f32) -> Option<f32> {
+    if input.is_nan() || input.is_infinite() {
+        None
+    } else {
+        Some(input)
+    }
+}
+
+/// The iced [`Length`] corresponding to a Taffy [`Dimension`](taffy::Dimension).
+///
+/// Every percentage becomes [`Length::Fill`]: the parent iced widget gives the container the same
+/// share of its space as any other filling widget, and Taffy then resolves the percentage against
+/// that share (the maximum of the container's limits).
+pub(crate) fn dimension_to_length(dimension: taffy::Dimension) -> Length {
+    match dimension {
+        taffy::Dimension::Points(points) => Length::Fixed(points),
+        taffy::Dimension::Auto => Length::Shrink,
+        taffy::Dimension::Percent(_) => Length::Fill,
+    }
+}
+
+/// The Taffy [`Dimension`](taffy::Dimension) corresponding to an iced [`Length`]. Taffy has no
+/// equivalent of [`Length::FillPortion`], which is treated as [`Length::Fill`]: the parent iced
+/// widget decides how much space the portion is given.
+pub(crate) fn length_to_dimension(length: Length) -> taffy::Dimension {
+    match length {
+        Length::Fill | Length::FillPortion(_) => taffy::Dimension::Percent(1.0),
+        Length::Shrink => taffy::Dimension::Auto,
+        Length::Fixed(points) => taffy::Dimension::Points(points),
+    }
+}
+
+pub(crate) struct TaffyLayoutTree<'node, 'a, 'b, Msg, R: Renderer> {
+    pub(crate) style: &'node taffy::Style,
+    pub(crate) children: &'node mut [TaffyChild<'a, Msg, R>],
+    pub(crate) renderer: &'b R,
+    /// The layout of the container itself
+    layout: taffy::Layout,
+    /// The nodes of the tree, indexed by their [`taffy::NodeId`]s: the container itself, then its
+    /// children, and then the items of any subgrids.
+    ///
+    /// Only subgrids are part of the tree. Other nested containers are opaque children, which lay
+    /// out their own trees when the container asks them for their size.
+    nodes: Vec<TreeNode>,
+}
+
+/// The id of the container itself in its [`TaffyLayoutTree`]
+pub(crate) const ROOT_NODE_ID: taffy::NodeId = taffy::NodeId::new(0);
+
+/// A node of a [`TaffyLayoutTree`]
+#[derive(Debug, Clone)]
+struct TreeNode {
+    /// The node which this node is a child of. For the items of a subgrid, this is the subgrid,
+    /// which is always a child of the container itself.
+    parent: usize,
+    /// The index of this node among the children of its parent's widget
+    index: usize,
+    /// The nodes laid out by this node, which are always contiguous
+    children: std::ops::Range<usize>,
+}
+
+/// Iterator over the ids of a node's children
+pub struct ChildIter(std::ops::Range<usize>);
+impl Iterator for ChildIter {
+    type Item = taffy::NodeId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|node| node.into())
+    }
+}
+
+impl<'node, 'a, 'b, Msg, R: Renderer> TaffyLayoutTree<'node, 'a, 'b, Msg, R> {
+    pub(crate) fn new(
+        style: &'node taffy::Style,
+        children: &'node mut [TaffyChild<'a, Msg, R>],
+        renderer: &'b R,
+    ) -> Self {
+        let mut nodes = vec![TreeNode {
+            parent: 0,
+            index: 0,
+            children: 0..0,
+        }];
+        nodes.extend((0..children.len()).map(|index| TreeNode {
+            parent: 0,
+            index,
+            children: 0..0,
+        }));
+
+        // The items of the container's subgrids follow its children. They are flattened into its
+        // own grid, so it lays them out along with its children.
+        for (index, child) in children.iter().enumerate() {
+            if let ChildWidget::Subgrid(subgrid) = &child.element {
+                let first_item = nodes.len();
+                nodes.extend((0..subgrid.container.children.len()).map(|item| TreeNode {
+                    parent: index + 1,
+                    index: item,
+                    children: 0..0,
+                }));
+                nodes[index + 1].children = first_item..nodes.len();
+            }
+        }
+        nodes[0].children = 1..nodes.len();
+
+        Self {
+            style,
+            children,
+            renderer,
+            layout: taffy::NULL_LAYOUT,
+            nodes,
+        }
+    }
+
+    /// The child or subgrid item which a (non-root) node refers to
+    fn resolve<'t>(
+        children: &'t [TaffyChild<'a, Msg, R>],
+        nodes: &[TreeNode],
+        node: usize,
+    ) -> &'t TaffyChild<'a, Msg, R> {
+        let TreeNode { parent, index, .. } = nodes[node];
+        if parent == 0 {
+            return &children[index];
+        }
+        &children[nodes[parent].index].element.items()[index]
+    }
+
+    /// The child or subgrid item which a (non-root) node refers to
+    fn resolve_mut<'t>(
+        children: &'t mut [TaffyChild<'a, Msg, R>],
+        nodes: &[TreeNode],
+        node: usize,
+    ) -> &'t mut TaffyChild<'a, Msg, R> {
+        let TreeNode { parent, index, .. } = nodes[node];
+        if parent == 0 {
+            return &mut children[index];
+        }
+        &mut children[nodes[parent].index].element.items_mut()[index]
+    }
+
+    fn child_mut(&mut self, node: taffy::NodeId) -> &mut TaffyChild<'a, Msg, R> {
+        Self::resolve_mut(self.children, &self.nodes, node.into())
+    }
+}
+
+/// Convert the constraints that Taffy places on a child into iced [`Limits`]
+fn child_limits(
+    known_dimensions: taffy::Size<Option<f32>>,
+    available_space: taffy::Size<taffy::AvailableSpace>,
+) -> Limits {
+    let mut limits = Limits::NONE;
+
+    // Set constraints based on available_space
+    if let taffy::AvailableSpace::Definite(height) = available_space.height {
+        limits = limits.max_height(height.round());
+    }
+    if let taffy::AvailableSpace::Definite(width) = available_space.width {
+        limits = limits.max_width(width.round());
+    }
+
+    // Set constraints based on known dimensions
+    if let Some(height) = known_dimensions.height {
+        limits = limits.height(Length::Fixed(height.round()))
+    }
+    if let Some(width) = known_dimensions.width {
+        limits = limits.width(Length::Fixed(width.round()))
+    }
+
+    limits
+}
+
+/// Find the min-content width of an iced widget: the narrowest width that its content fits in
+/// (which for wrapping text is the width of its longest word).
+///
+/// iced widgets have no notion of intrinsic sizes, and clamp the size they report to their
+/// limits, so this searches for the narrowest maximum width at which the widget reports a width
+/// narrower than the maximum. The widget's max-content width (its width with no maximum) bounds
+/// the search.
+fn min_content_width<Msg, R: Renderer>(
+    widget: &mut dyn Widget<Msg, R>,
+    renderer: &R,
+    limits: &Limits,
+) -> f32 {
+    let max_content_width = widget.measure(renderer, limits).width;
+    if !max_content_width.is_finite() {
+        return max_content_width;
+    }
+
+    let mut narrowest_fitting = max_content_width.ceil();
+    let mut widest_overflowing = 0.0;
+    while narrowest_fitting - widest_overflowing > 1.0 {
+        let width = ((narrowest_fitting + widest_overflowing) / 2.0).floor();
+        if widget.measure(renderer, &limits.max_width(width)).width < width {
+            narrowest_fitting = width;
+        } else {
+            widest_overflowing = width;
+        }
+    }
+    narrowest_fitting
+}
+
+/// Whether Taffy is asking for a child's min-content width, which must be found by probing
+fn is_min_content_query(
+    known_dimensions: taffy::Size<Option<f32>>,
+    available_space: taffy::Size<taffy::AvailableSpace>,
+) -> bool {
+    known_dimensions.width.is_none()
+        && matches!(available_space.width, taffy::AvailableSpace::MinContent)
+}
+
+impl<'node, 'a, 'b, Msg, R: Renderer> taffy::LayoutTree for TaffyLayoutTree<'node, 'a, 'b, Msg, R> {
+    type ChildIter<'iter>
+        = ChildIter
+    where
+        Self: 'iter;
+
+    fn style(&self, node: taffy::NodeId) -> &taffy::Style {
+        let node: usize = node.into();
+        if node == 0 {
+            return self.style;
+        }
+        let TreeNode { parent, index, .. } = self.nodes[node];
+        if parent != 0 {
+            // The items of the container's subgrids are placed using their translated styles
+            let subgrid = &self.children[self.nodes[parent].index];
+            return &subgrid.element.item_styles()[index];
+        }
+        &self.children[index].style
+    }
+
+    fn layout_mut(&mut self, node: taffy::NodeId) -> &mut taffy::Layout {
+        if node == ROOT_NODE_ID {
+            &mut self.layout
+        } else {
+            &mut self.child_mut(node).taffy_layout
+        }
+    }
+
+    fn children(&self, node: taffy::NodeId) -> Self::ChildIter<'_> {
+        let node: usize = node.into();
+        ChildIter(self.nodes[node].children.clone())
+    }
+
+    fn child_count(&self, node: taffy::NodeId) -> usize {
+        let node: usize = node.into();
+        self.nodes[node].children.len()
+    }
+
+    fn child(&self, node: taffy::NodeId, index: usize) -> taffy::NodeId {
+        let node: usize = node.into();
+        (self.nodes[node].children.start + index).into()
+    }
+
+    fn measure_child_size(
+        &mut self,
+        child_node_id: taffy::NodeId,
+        known_dimensions: taffy::Size<Option<f32>>,
+        parent_size: taffy::Size<Option<f32>>,
+        available_space: taffy::Size<taffy::AvailableSpace>,
+        sizing_mode: taffy::SizingMode,
+    ) -> taffy::Size<f32> {
+        let renderer = self.renderer;
+        let child = self.child_mut(child_node_id);
+        if child.is_hidden() {
+            return taffy::Size::ZERO;
+        }
+        if let ChildWidget::Subgrid(_) = child.element {
+            // A subgrid's items are sized as part of this grid, so it contributes no size itself
+            return known_dimensions.unwrap_or(taffy::Size::ZERO);
+        }
+        let cached_size = child.cache.get(
+            known_dimensions,
+            available_space,
+            taffy::RunMode::ComputeSize,
+        );
+
+        let size = cached_size
+            .map(|size_and_baselines| size_and_baselines.size)
+            .unwrap_or_else(|| {
+                // Compute child layout
+                // Under a max-content constraint the child is given an unbounded width, so that
+                // text does not wrap
+                let widget = child.element.as_widget_mut();
+                let limits = child_limits(known_dimensions, available_space);
+                let (mut iced_size, answered) = with_intrinsic_query(
+                    &limits,
+                    known_dimensions,
+                    parent_size,
+                    available_space,
+                    sizing_mode,
+                    || widget.measure(renderer, &limits),
+                );
+                if !answered && is_min_content_query(known_dimensions, available_space) {
+                    let width = min_content_width(widget, renderer, &limits);
+                    iced_size = widget.measure(renderer, &limits.max_width(width));
+                }
+                let taffy_size = taffy::Size {
+                    width: iced_size.width,
+                    height: iced_size.height,
+                };
+                child.cache.store(
+                    known_dimensions,
+                    available_space,
+                    taffy::RunMode::ComputeSize,
+                    taffy_size.into(),
+                );
+                taffy_size
+            });
+
+        // Return size
+        size
+    }
+
+    fn perform_child_layout(
+        &mut self,
+        child_node_id: taffy::NodeId,
+        known_dimensions: taffy::Size<Option<f32>>,
+        parent_size: taffy::Size<Option<f32>>,
+        available_space: taffy::Size<taffy::AvailableSpace>,
+        sizing_mode: taffy::SizingMode,
+    ) -> taffy::SizeAndBaselines {
+        let renderer = self.renderer;
+        let child = self.child_mut(child_node_id);
+        if child.is_hidden() {
+            return taffy::SizeAndBaselines {
+                size: taffy::Size::ZERO,
+                first_baselines: taffy::Point::NONE,
+            };
+        }
+        if let ChildWidget::Subgrid(_) = child.element {
+            // A subgrid fills its grid area. Its items are positioned by this grid.
+            return known_dimensions.unwrap_or(taffy::Size::ZERO).into();
+        }
+        let cached_layout = child.cache.get(
+            known_dimensions,
+            available_space,
+            taffy::RunMode::PeformLayout,
+        );
+
+        let layout = cached_layout.unwrap_or_else(|| {
+            // Compute child layout, collecting the baseline it reports (if any)
+            clear_first_baseline();
+            let widget = child.element.as_widget_mut();
+            let limits = child_limits(known_dimensions, available_space);
+            let (mut iced_layout, answered) = with_intrinsic_query(
+                &limits,
+                known_dimensions,
+                parent_size,
+                available_space,
+                sizing_mode,
+                || widget.layout(renderer, &limits),
+            );
+            if !answered && is_min_content_query(known_dimensions, available_space) {
+                let width = min_content_width(widget, renderer, &limits);
+                clear_first_baseline();
+                iced_layout = widget.layout(renderer, &limits.max_width(width));
+            }
+            let bounds = iced_layout.bounds();
+            let taffy_layout = taffy::SizeAndBaselines {
+                size: taffy::Size {
+                    width: bounds.width,
+                    height: bounds.height,
+                },
+                first_baselines: taffy::Point {
+                    x: None,
+                    y: take_first_baseline(&*widget),
+                },
+            };
+            child.cache.store(
+                known_dimensions,
+                available_space,
+                taffy::RunMode::PeformLayout,
+                taffy_layout,
+            );
+            child.iced_child_layouts = iced_layout.into_children();
+            taffy_layout
+        });
+
+        // Return size
+        layout
+    }
+}
+
+impl<'node, 'a, 'b, Msg, R: Renderer> TaffyLayoutTree<'node, 'a, 'b, Msg, R> {
+    /// Give a child with `display: None` an empty layout.
+    ///
+    /// Taffy's algorithms skip hidden children entirely, so this is called for each of them once
+    /// the container's algorithm has run.
+    pub(crate) fn perform_child_hidden_layout(&mut self, child_index: usize, order: u32) {
+        let child = &mut self.children[child_index];
+        child.taffy_layout = taffy::Layout {
+            order,
+            ..taffy::NULL_LAYOUT
+        };
+        child.iced_child_layouts.clear();
+    }
+}
+
+/// The widget of a [`TaffyChild`]
+pub(crate) enum ChildWidget<'a, Msg, R: Renderer> {
+    Element(Element<'a, Msg, R>),
+    /// A [`Grid`] whose items are laid out in the tracks of its parent grid
+    Subgrid(Box<Grid<'a, Msg, R>>),
+}
+
+impl<'a, Msg, R: Renderer> ChildWidget<'a, Msg, R> {
+    pub(crate) fn as_widget(&self) -> &dyn Widget<Msg, R> {
+        match self {
+            ChildWidget::Element(element) => element.as_widget(),
+            ChildWidget::Subgrid(subgrid) => subgrid.as_ref(),
+        }
+    }
+
+    pub(crate) fn as_widget_mut(&mut self) -> &mut dyn Widget<Msg, R> {
+        match self {
+            ChildWidget::Element(element) => element.as_widget_mut(),
+            ChildWidget::Subgrid(subgrid) => subgrid.as_mut(),
+        }
+    }
+
+    /// The items of a subgrid, which are nodes of its parent grid's tree. Other widgets have none.
+    fn items(&self) -> &[TaffyChild<'a, Msg, R>] {
+        match self {
+            ChildWidget::Element(_) => &[],
+            ChildWidget::Subgrid(subgrid) => &subgrid.container.children,
+        }
+    }
+
+    fn items_mut(&mut self) -> &mut [TaffyChild<'a, Msg, R>] {
+        match self {
+            ChildWidget::Element(_) => &mut [],
+            ChildWidget::Subgrid(subgrid) => &mut subgrid.container.children,
+        }
+    }
+
+    /// The styles with which a subgrid's items are placed in the tracks of its parent grid
+    fn item_styles(&self) -> &[taffy::Style] {
+        match self {
+            ChildWidget::Element(_) => &[],
+            ChildWidget::Subgrid(subgrid) => &subgrid.flattened_styles,
+        }
+    }
+}
+
+/// An iced [`Element`] along with the Taffy style and layout state used to position it
+pub(crate) struct TaffyChild<'a, Msg, R: Renderer> {
+    pub(crate) element: ChildWidget<'a, Msg, R>,
+    /// The style used to lay out the child: its explicit style, with the defaults filled in from
+    /// the iced [`Length`]s of its widget
+    pub(crate) style: taffy::Style,
+    /// The style which the child was given when it was added to its container
+    pub(crate) explicit_style: taffy::Style,
+    pub(crate) cache: taffy::Cache,
+    pub(crate) taffy_layout: taffy::Layout,
+    pub(crate) iced_child_layouts: Vec<iced_native::layout::Node>,
+}
+
+impl<'a, Msg, R: Renderer> TaffyChild<'a, Msg, R> {
+    pub(crate) fn new(element: ChildWidget<'a, Msg, R>, style: taffy::Style) -> Self {
+        Self {
+            element,
+            style: style.clone(),
+            explicit_style: style,
+            cache: taffy::Cache::new(),
+            taffy_layout: taffy::NULL_LAYOUT,
+            iced_child_layouts: vec![],
+        }
+    }
+
+    /// Whether the child has `display: None` and should therefore be neither laid out, drawn
+    /// nor sent events
+    pub(crate) fn is_hidden(&self) -> bool {
+        self.style.display == taffy::Display::None
+    }
+}
@@ -0,0 +1,665 @@
+//! Grids which only build the items that can be seen
+//!
+//! A [`VirtualGrid`] has a fixed number of items, which are arranged in rows across its column
+//! tracks and produced on demand by a closure. Its height is derived from the number of rows and
+//! their (uniform or estimated) height. Only the items in the rows (and, if every column has a
+//! fixed width, the columns) which intersect the viewport, or lie within [`OVERSCAN`] of it, are
+//! built and laid out, as the children of an ordinary [`Grid`].
+//!
+//! The items are built when the grid is laid out, around the viewport it was last drawn in, so the
+//! rows in view are measured before its height is reported. Once it is scrolled to within half of
+//! [`OVERSCAN`] of the edge of the built items, the grid invalidates the layout on its next event
+//! so that the items around the new viewport are built.
+//!
+//! The widget [`Tree`]s of the items are kept while they remain built, and dropped once they are
+//! scrolled out of view.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::ops::Range;
+use std::rc::Rc;
+
+use iced_native::event::{self, Event};
+use iced_native::renderer::Renderer;
+use iced_native::widget::{tree, Operation, Tree};
+use iced_native::{layout, mouse, overlay, renderer};
+use iced_native::{
+    Clipboard, Element, Layout, Length, Point, Rectangle, Shell, Size, Vector, Widget,
+};
+
+use crate::container::ContainerState;
+use crate::grid::Grid;
+use crate::tree::taffy;
+
+/// The height of the rows of a [`VirtualGrid`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RowHeight {
+    /// Every row has exactly this height
+    Uniform(f32),
+    /// Rows are sized to fit their items, and rows which have not been built yet are assumed to
+    /// have this height. The height of the grid is corrected as rows are built.
+    Estimated(f32),
+}
+
+/// How far beyond the viewport items are built, in points, so that scrolling a short distance
+/// does not build the items again
+const OVERSCAN: f32 = 200.0;
+
+/// What a [`VirtualGrid`] has learned from being laid out and drawn
+#[derive(Debug, Default)]
+struct Measurements {
+    /// The heights of the rows which have been built
+    rows: Vec<Option<f32>>,
+    /// The part of the grid which was visible when it was last drawn, relative to its top left
+    /// corner
+    viewport: Option<Rectangle>,
+}
+
+/// The [`Measurements`] of a grid, shared between the widget [`Tree`] state and the grid built by
+/// the latest `view()`
+type SharedMeasurements = Rc<RefCell<Measurements>>;
+
+/// The widget [`Tree`] state of a [`VirtualGrid`]
+struct VirtualState {
+    /// The state of the grid of built items, whose children are the states of the items
+    window_tree: Tree,
+    /// The indices of the items whose states are the children of `window_tree`
+    window_items: Vec<usize>,
+    measurements: SharedMeasurements,
+}
+
+impl Default for VirtualState {
+    fn default() -> Self {
+        Self {
+            window_tree: Tree::empty(),
+            window_items: Vec::new(),
+            measurements: SharedMeasurements::default(),
+        }
+    }
+}
+
+/// The items which are currently built, laid out as a [`Grid`]
+struct Window<'a, Msg, R: Renderer> {
+    rows: Range<usize>,
+    columns: Range<usize>,
+    /// The indices of the children of `grid`
+    items: Vec<usize>,
+    grid: Grid<'a, Msg, R>,
+    node: layout::Node,
+    /// The position of the window within the virtual grid
+    offset: Vector,
+    /// Whether the widget [`Tree`] state holds the states of `items`
+    synced: Cell<bool>,
+}
+
+impl<'a, Msg, R: Renderer> Window<'a, Msg, R> {
+    fn covers(&self, rows: &Range<usize>, columns: &Range<usize>) -> bool {
+        self.rows.start <= rows.start
+            && rows.end <= self.rows.end
+            && self.columns.start <= columns.start
+            && columns.end <= self.columns.end
+    }
+
+    /// Bring the widget [`Tree`] state up to date with the items of the window, keeping the
+    /// state of the grid and of the items which were already built
+    fn sync(&self, state: &mut VirtualState) {
+        if self.synced.replace(true) {
+            return;
+        }
+        // The states of the items which remain built are moved to their new positions, along with
+        // the grid's references to them
+        let mut old_trees: HashMap<usize, (usize, Tree)> = state
+            .window_items
+            .drain(..)
+            .enumerate()
+            .zip(std::mem::take(&mut state.window_tree.children))
+            .map(|((position, index), tree)| (index, (position, tree)))
+            .collect();
+        let mut new_positions = HashMap::new();
+        let children = self
+            .items
+            .iter()
+            .enumerate()
+            .zip(self.grid.container.children.iter())
+            .map(|((position, index), child)| match old_trees.remove(index) {
+                Some((old_position, tree)) => {
+                    new_positions.insert(old_position, position);
+                    tree
+                }
+                None => Tree::new(child.element.as_widget()),
+            })
+            .collect();
+
+        if state.window_tree.tag == self.grid.tag() {
+            state
+                .window_tree
+                .state
+                .downcast_mut::<ContainerState>()
+                .remap_children(|position| new_positions.get(&position).copied());
+        } else {
+            state.window_tree = Tree {
+                tag: self.grid.tag(),
+                state: self.grid.state(),
+                children: Vec::new(),
+            };
+        }
+        state.window_tree.children = children;
+        self.grid.diff(&mut state.window_tree);
+        state.window_items = self.items.clone();
+    }
+}
+
+/// The vertical geometry of the rows of a [`VirtualGrid`]
+struct Rows<'m> {
+    count: usize,
+    row_height: RowHeight,
+    gap: f32,
+    measured: &'m [Option<f32>],
+}
+
+impl<'m> Rows<'m> {
+    fn height(&self, row: usize) -> f32 {
+        match self.row_height {
+            RowHeight::Uniform(height) => height,
+            RowHeight::Estimated(estimate) => self
+                .measured
+                .get(row)
+                .copied()
+                .flatten()
+                .unwrap_or(estimate),
+        }
+    }
+
+    fn total_height(&self) -> f32 {
+        if self.count == 0 {
+            return 0.0;
+        }
+        let gaps = (self.count - 1) as f32 * self.gap;
+        match self.row_height {
+            RowHeight::Uniform(height) => self.count as f32 * height + gaps,
+            RowHeight::Estimated(_) => {
+                (0..self.count).map(|row| self.height(row)).sum::<f32>() + gaps
+            }
+        }
+    }
+
+    /// The rows which overlap the range from `top` to `bottom`, and the offset of the first of them
+    fn visible(&self, top: f32, bottom: f32) -> (Range<usize>, f32) {
+        if let RowHeight::Uniform(height) = self.row_height {
+            let stride = height + self.gap;
+            let first = ((top / stride).floor().max(0.0) as usize).min(self.count);
+            let end = ((bottom / stride).ceil().max(0.0) as usize).clamp(first, self.count);
+            return (first..end, first as f32 * stride);
+        }
+        visible_tracks(
+            (0..self.count).map(|row| self.height(row)),
+            self.gap,
+            top,
+            bottom,
+        )
+    }
+}
+
+/// The tracks (of the given sizes, separated by `gap`) which overlap the range from `start` to
+/// `end`, and the offset of the first of them
+fn visible_tracks(
+    sizes: impl Iterator<Item = f32>,
+    gap: f32,
+    start: f32,
+    end: f32,
+) -> (Range<usize>, f32) {
+    let mut first = None;
+    let mut first_offset = 0.0;
+    let mut offset = 0.0;
+    let mut index = 0;
+    for size in sizes {
+        if offset > end {
+            break;
+        }
+        if first.is_none() && offset + size >= start {
+            first = Some(index);
+            first_offset = offset;
+        }
+        offset += size + gap;
+        index += 1;
+    }
+    let first = first.unwrap_or(index);
+    (first..index, first_offset)
+}
+
+/// The widths of the columns if every column track has a fixed width, so that the columns can be
+/// positioned without building any items
+fn fixed_column_widths(columns: &[taffy::TrackSizingFunction]) -> Option<Vec<f32>> {
+    columns
+        .iter()
+        .map(|column| match column {
+            taffy::TrackSizingFunction::Single(taffy::MinMax {
+                min: taffy::MinTrackSizingFunction::Fixed(taffy::LengthPercentage::Points(min)),
+                max: taffy::MaxTrackSizingFunction::Fixed(taffy::LengthPercentage::Points(max)),
+            }) if min == max => Some(*min),
+            _ => None,
+        })
+        .collect()
+}
+
+/// A grid whose items are produced lazily, of which only those in view are built, laid out and
+/// drawn.
+///
+/// Items are placed in order along each row, one per column track. Items cannot be placed or
+/// span several tracks.
+pub struct VirtualGrid<'a, Msg, R: Renderer> {
+    item_count: usize,
+    view: Box<dyn Fn(usize) -> Element<'a, Msg, R> + 'a>,
+    columns: Vec<taffy::TrackSizingFunction>,
+    row_height: RowHeight,
+    column_gap: f32,
+    row_gap: f32,
+    width: Length,
+    /// The measurements in the grid's widget `Tree` state, picked up in `diff`
+    pending_measurements: Cell<Option<SharedMeasurements>>,
+    measurements: Option<SharedMeasurements>,
+    window: Option<Window<'a, Msg, R>>,
+}
+
+impl<'a, Msg, R: Renderer> VirtualGrid<'a, Msg, R> {
+    /// Creates a [`VirtualGrid`] of `item_count` items, where `view` builds the item of each index
+    pub fn new(item_count: usize, view: impl Fn(usize) -> Element<'a, Msg, R> + 'a) -> Self {
+        Self {
+            item_count,
+            view: Box::new(view),
+            columns: vec![taffy::style_helpers::flex(1.0)],
+            row_height: RowHeight::Estimated(40.0),
+            column_gap: 0.0,
+            row_gap: 0.0,
+            width: Length::Fill,
+            pending_measurements: Cell::new(None),
+            measurements: None,
+            window: None,
+        }
+    }
+
+    /// Sets the column tracks which each row of items is laid out in. These are sized like the
+    /// tracks of a [`Grid`], from the items which are in view.
+    ///
+    /// If every track has a fixed size (such as `points(120.0)`), only the columns in view are
+    /// built.
+    pub fn with_columns(mut self, columns: Vec<taffy::TrackSizingFunction>) -> Self {
+        if !columns.is_empty() {
+            self.columns = columns;
+        }
+        self
+    }
+
+    /// Sets the height of the rows. Defaults to [`RowHeight::Estimated`] with a height of 40.
+    pub fn row_height(mut self, row_height: RowHeight) -> Self {
+        self.row_height = row_height;
+        self
+    }
+
+    /// Sets the gap between columns, in points
+    pub fn with_column_gap(mut self, gap: f32) -> Self {
+        self.column_gap = gap;
+        self
+    }
+
+    /// Sets the gap between rows, in points
+    pub fn with_row_gap(mut self, gap: f32) -> Self {
+        self.row_gap = gap;
+        self
+    }
+
+    /// Sets the width of the [`VirtualGrid`]. Defaults to [`Length::Fill`].
+    pub fn width(mut self, width: Length) -> Self {
+        self.width = width;
+        self
+    }
+
+    fn row_count(&self) -> usize {
+        (self.item_count + self.columns.len() - 1) / self.columns.len()
+    }
+
+    /// The measurements shared with the widget `Tree` state, once `diff` has passed them on
+    fn measurements(&mut self) -> SharedMeasurements {
+        if let Some(measurements) = self.pending_measurements.get_mut().take() {
+            self.measurements = Some(measurements);
+        }
+        self.measurements
+            .get_or_insert_with(SharedMeasurements::default)
+            .clone()
+    }
+
+    fn rows<'m>(&self, measured: &'m [Option<f32>]) -> Rows<'m> {
+        Rows {
+            count: self.row_count(),
+            row_height: self.row_height,
+            gap: self.row_gap,
+            measured,
+        }
+    }
+
+    /// The rows and columns which intersect `visible` (relative to the grid) extended by `margin`
+    /// on every side, and the offset of the first of them
+    fn tracks_in(
+        &self,
+        measured: &[Option<f32>],
+        visible: Rectangle,
+        margin: f32,
+    ) -> (Range<usize>, Range<usize>, Vector) {
+        let (rows, row_offset) = self
+            .rows(measured)
+            .visible(visible.y - margin, visible.y + visible.height + margin);
+        let (columns, column_offset) = match fixed_column_widths(&self.columns) {
+            Some(widths) => visible_tracks(
+                widths.into_iter(),
+                self.column_gap,
+                visible.x - margin,
+                visible.x + visible.width + margin,
+            ),
+            None => (0..self.columns.len(), 0.0),
+        };
+        (rows, columns, Vector::new(column_offset, row_offset))
+    }
+
+    /// The size of the grid within `limits`, from the heights of its rows
+    fn size(&self, limits: &layout::Limits) -> Size {
+        let measurements = self
+            .measurements
+            .as_ref()
+            .map(|measurements| measurements.borrow());
+        let rows = self.rows(
+            measurements
+                .as_deref()
+                .map_or(&[][..], |measurements| measurements.rows.as_slice()),
+        );
+        let (min, max) = (limits.min(), limits.max());
+        let width = match self.width {
+            Length::Fixed(width) => width.clamp(min.width, max.width),
+            _ if max.width.is_finite() => max.width,
+            _ => min.width,
+        };
+        Size::new(width, rows.total_height().clamp(min.height, max.height))
+    }
+
+    /// Build and lay out the items which intersect `visible` (relative to the grid), or lie within
+    /// [`OVERSCAN`] of it, and record the heights of their rows
+    fn build_window(&mut self, renderer: &R, width: f32, visible: Rectangle) {
+        let column_count = self.columns.len();
+        let measurements = self.measurements();
+        let mut measurements = measurements.borrow_mut();
+        measurements.rows.resize(self.row_count(), None);
+        let (rows, columns, offset) = self.tracks_in(&measurements.rows, visible, OVERSCAN);
+
+        let window_width = match fixed_column_widths(&self.columns) {
+            Some(widths) => {
+                widths[columns.clone()].iter().sum::<f32>()
+                    + columns.len().saturating_sub(1) as f32 * self.column_gap
+            }
+            None => width,
+        };
+        let row_track: taffy::TrackSizingFunction = match self.row_height {
+            RowHeight::Uniform(height) => taffy::style_helpers::points(height),
+            RowHeight::Estimated(_) => taffy::style_helpers::auto(),
+        };
+        let mut grid = Grid::new()
+            .with_columns(self.columns[columns.clone()].to_vec())
+            .with_rows(vec![row_track; rows.len()])
+            .with_column_gap(taffy::style_helpers::points(self.column_gap))
+            .with_row_gap(taffy::style_helpers::points(self.row_gap))
+            .width(Length::Fixed(window_width))
+            .height(Length::Shrink);
+        let mut items = Vec::with_capacity(rows.len() * columns.len());
+        for row in rows.clone() {
+            for column in columns.clone() {
+                let index = row * column_count + column;
+                if index >= self.item_count {
+                    break;
+                }
+                grid = grid.with_styled_child((self.view)(index), |style| {
+                    style.grid_row = taffy::style_helpers::line((row - rows.start + 1) as i16);
+                    style.grid_column =
+                        taffy::style_helpers::line((column - columns.start + 1) as i16);
+                });
+                items.push(index);
+            }
+        }
+
+        let node = grid.layout(
+            renderer,
+            &layout::Limits::new(
+                Size::new(window_width, 0.0),
+                Size::new(window_width, f32::INFINITY),
+            ),
+        );
+
+        if let RowHeight::Estimated(_) = self.row_height {
+            // Each row is as tall as its tallest item
+            let mut heights = vec![None::<f32>; rows.len()];
+            for (index, child) in items.iter().zip(node.children()) {
+                let height = &mut heights[index / column_count - rows.start];
+                *height = Some(height.unwrap_or(0.0).max(child.bounds().height));
+            }
+            for (row, height) in rows.clone().zip(heights) {
+                if height.is_some() {
+                    measurements.rows[row] = height;
+                }
+            }
+        }
+
+        self.window = Some(Window {
+            rows,
+            columns,
+            items,
+            grid,
+            node,
+            offset,
+            synced: Cell::new(false),
+        });
+    }
+
+    /// Whether the viewport that the grid was last drawn in has come within half of [`OVERSCAN`]
+    /// of the edge of the built items, so that they should be built again around it
+    fn needs_rebuild(&mut self) -> bool {
+        let measurements = self.measurements();
+        let measurements = measurements.borrow();
+        match (&self.window, measurements.viewport) {
+            (Some(window), Some(visible)) => {
+                let (rows, columns, _) =
+                    self.tracks_in(&measurements.rows, visible, OVERSCAN / 2.0);
+                !window.covers(&rows, &columns)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Creates a [`VirtualGrid`] of `item_count` items, where `view` builds the item of each index
+pub fn virtual_grid<'a, Msg, R: Renderer>(
+    item_count: usize,
+    view: impl Fn(usize) -> Element<'a, Msg, R> + 'a,
+) -> VirtualGrid<'a, Msg, R> {
+    VirtualGrid::new(item_count, view)
+}
+
+/// The layout of a window with the given node and offset, positioned within the virtual grid
+/// whose layout is `layout`
+fn window_layout<'n>(node: &'n layout::Node, offset: Vector, layout: Layout<'_>) -> Layout<'n> {
+    let position = layout.bounds().position();
+    Layout::with_offset(
+        Vector::new(position.x + offset.x, position.y + offset.y),
+        node,
+    )
+}
+
+impl<'a, Msg, R: Renderer> Widget<Msg, R> for VirtualGrid<'a, Msg, R> {
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<RefCell<VirtualState>>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(RefCell::new(VirtualState::default()))
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        let state = tree.state.downcast_ref::<RefCell<VirtualState>>();
+        self.pending_measurements
+            .set(Some(state.borrow().measurements.clone()));
+    }
+
+    fn width(&self) -> Length {
+        self.width
+    }
+
+    fn height(&self) -> Length {
+        Length::Shrink
+    }
+
+    fn measure(&mut self, _renderer: &R, limits: &layout::Limits) -> Size {
+        self.measurements();
+        self.size(limits)
+    }
+
+    fn layout(&mut self, renderer: &R, limits: &layout::Limits) -> layout::Node {
+        let measurements = self.measurements();
+        let width = self.size(limits).width;
+        // Build the items around the viewport that the grid was last drawn in (or at its top, if
+        // it has not been drawn yet), so that their rows are measured before its height is known
+        let viewport = measurements.borrow().viewport;
+        let visible = viewport.unwrap_or(Rectangle::new(Point::ORIGIN, Size::new(width, 0.0)));
+        self.build_window(renderer, width, visible);
+        layout::Node::new(self.size(limits))
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &R,
+        operation: &mut dyn Operation<Msg>,
+    ) {
+        if let Some(window) = &self.window {
+            let state = tree.state.downcast_mut::<RefCell<VirtualState>>().get_mut();
+            window.sync(state);
+            window.grid.operate(
+                &mut state.window_tree,
+                window_layout(&window.node, window.offset, layout),
+                renderer,
+                operation,
+            );
+        }
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &R,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Msg>,
+    ) -> event::Status {
+        // The items around the viewport can only be built by laying the grid out again
+        if self.needs_rebuild() {
+            shell.invalidate_layout();
+        }
+        let state = tree.state.downcast_mut::<RefCell<VirtualState>>().get_mut();
+        let Some(window) = &mut self.window else {
+            return event::Status::Ignored;
+        };
+        window.sync(state);
+        let window_layout = window_layout(&window.node, window.offset, layout);
+        window.grid.on_event(
+            &mut state.window_tree,
+            event,
+            window_layout,
+            cursor_position,
+            renderer,
+            clipboard,
+            shell,
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+        renderer: &R,
+    ) -> mouse::Interaction {
+        let Some(window) = &self.window else {
+            return mouse::Interaction::default();
+        };
+        let mut state = tree
+            .state
+            .downcast_ref::<RefCell<VirtualState>>()
+            .borrow_mut();
+        window.sync(&mut state);
+        window.grid.mouse_interaction(
+            &state.window_tree,
+            window_layout(&window.node, window.offset, layout),
+            cursor_position,
+            viewport,
+            renderer,
+        )
+    }
+
+    fn draw(
+        &mut self,
+        tree: &Tree,
+        renderer: &mut R,
+        theme: &R::Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+    ) {
+        // The items are built around this viewport when the grid is next laid out
+        let bounds = layout.bounds();
+        self.measurements().borrow_mut().viewport = Some(Rectangle {
+            x: viewport.x - bounds.x,
+            y: viewport.y - bounds.y,
+            ..*viewport
+        });
+        let Some(window) = &mut self.window else {
+            return;
+        };
+        let mut state = tree
+            .state
+            .downcast_ref::<RefCell<VirtualState>>()
+            .borrow_mut();
+        window.sync(&mut state);
+        let window_layout = window_layout(&window.node, window.offset, layout);
+        window.grid.draw(
+            &state.window_tree,
+            renderer,
+            theme,
+            style,
+            window_layout,
+            cursor_position,
+            viewport,
+        );
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'_>,
+        renderer: &R,
+    ) -> Option<overlay::Element<'b, Msg, R>> {
+        let window = self.window.as_mut()?;
+        let state = tree.state.downcast_mut::<RefCell<VirtualState>>().get_mut();
+        window.sync(state);
+        window.grid.overlay(
+            &mut state.window_tree,
+            window_layout(&window.node, window.offset, layout),
+            renderer,
+        )
+    }
+}
+
+impl<'a, Msg: 'a, R: Renderer + 'a> From<VirtualGrid<'a, Msg, R>> for Element<'a, Msg, R> {
+    fn from(virtual_grid: VirtualGrid<'a, Msg, R>) -> Self {
+        Self::new(virtual_grid)
+    }
+}
@@ -0,0 +1,180 @@
+//! Controlling the order in which overlapping children are drawn
+//!
+//! Children of iced_taffy containers are drawn in the order that they were added, so later
+//! children are drawn over earlier ones. A child wrapped with [`with_z_index`] is instead drawn
+//! in order of its z-index (with children of equal z-index kept in insertion order), and the
+//! same order decides which children receive events and set the mouse cursor first.
+
+use iced_native::event::{self, Event};
+use iced_native::renderer::Renderer;
+use iced_native::widget::{tree, Operation, Tree};
+use iced_native::{layout, mouse, overlay, renderer};
+use iced_native::{Clipboard, Element, Layout, Length, Point, Rectangle, Shell, Size, Widget};
+
+use crate::baseline::{report_first_baseline, take_first_baseline};
+
+/// The state of a [`WithZIndex`], which gives its container access to the z-index
+pub(crate) struct ZIndexState(i32);
+
+/// The z-index of the child whose widget `Tree` is `tree`. Children which were not given one
+/// have a z-index of 0.
+pub(crate) fn z_index(tree: &Tree) -> i32 {
+    if tree.tag == tree::Tag::of::<ZIndexState>() {
+        tree.state.downcast_ref::<ZIndexState>().0
+    } else {
+        0
+    }
+}
+
+/// A widget which is drawn above or below its siblings according to its z-index
+pub struct WithZIndex<'a, Msg, R: Renderer> {
+    content: Element<'a, Msg, R>,
+    z_index: i32,
+}
+
+impl<'a, Msg, R: Renderer> WithZIndex<'a, Msg, R> {
+    /// Creates a [`WithZIndex`] with the given z-index
+    pub fn new(content: impl Into<Element<'a, Msg, R>>, z_index: i32) -> Self {
+        Self {
+            content: content.into(),
+            z_index,
+        }
+    }
+}
+
+/// Give `content` a z-index within its iced_taffy container.
+///
+/// Children with a higher z-index are drawn over those with a lower one, and are sent events and
+/// asked for a mouse cursor before them. Children default to a z-index of 0.
+pub fn with_z_index<'a, Msg, R: Renderer>(
+    content: impl Into<Element<'a, Msg, R>>,
+    z_index: i32,
+) -> WithZIndex<'a, Msg, R> {
+    WithZIndex::new(content, z_index)
+}
+
+impl<'a, Msg, R: Renderer> Widget<Msg, R> for WithZIndex<'a, Msg, R> {
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<ZIndexState>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(ZIndexState(self.z_index))
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(self.content.as_widget())]
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        *tree.state.downcast_mut::<ZIndexState>() = ZIndexState(self.z_index);
+        tree.diff_children(&[self.content.as_widget()])
+    }
+
+    fn width(&self) -> Length {
+        self.content.as_widget().width()
+    }
+
+    fn height(&self) -> Length {
+        self.content.as_widget().height()
+    }
+
+    fn measure(&mut self, renderer: &R, limits: &layout::Limits) -> Size {
+        self.content.as_widget_mut().measure(renderer, limits)
+    }
+
+    fn layout(&mut self, renderer: &R, limits: &layout::Limits) -> layout::Node {
+        let node = self.content.as_widget_mut().layout(renderer, limits);
+        let first_baseline = take_first_baseline(self.content.as_widget());
+        report_first_baseline(&*self, first_baseline);
+        node
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &R,
+        operation: &mut dyn Operation<Msg>,
+    ) {
+        self.content
+            .as_widget()
+            .operate(&mut tree.children[0], layout, renderer, operation)
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &R,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Msg>,
+    ) -> event::Status {
+        self.content.as_widget_mut().on_event(
+            &mut tree.children[0],
+            event,
+            layout,
+            cursor_position,
+            renderer,
+            clipboard,
+            shell,
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+        renderer: &R,
+    ) -> mouse::Interaction {
+        self.content.as_widget().mouse_interaction(
+            &tree.children[0],
+            layout,
+            cursor_position,
+            viewport,
+            renderer,
+        )
+    }
+
+    fn draw(
+        &mut self,
+        tree: &Tree,
+        renderer: &mut R,
+        theme: &R::Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+    ) {
+        self.content.as_widget_mut().draw(
+            &tree.children[0],
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor_position,
+            viewport,
+        )
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'_>,
+        renderer: &R,
+    ) -> Option<overlay::Element<'b, Msg, R>> {
+        self.content
+            .as_widget_mut()
+            .overlay(&mut tree.children[0], layout, renderer)
+    }
+}
+
+impl<'a, Msg: 'a, R: Renderer + 'a> From<WithZIndex<'a, Msg, R>> for Element<'a, Msg, R> {
+    fn from(with_z_index: WithZIndex<'a, Msg, R>) -> Self {
+        Self::new(with_z_index)
+    }
+}